@@ -6,7 +6,7 @@
 use cortex_m_rt::entry; // The runtime
 use stm32f7xx_hal::{self as hal, gpio::GpioExt, pac, prelude::*};
 
-use lcd1602_diver::{Cursor, CursorBlink, Display, DisplayMode, LCD1602};
+use lcd1602_diver::{Cursor, CursorBlink, Display, DisplayMode, LCD1602WithDelay};
 
 // I2C address of the LCD1602
 const I2C_ADDRESS: u8 = 0x27;
@@ -35,27 +35,24 @@ fn main() -> ! {
     );
 
     // Create a delay abstraction based on general-pupose 32-bit timer TIM5
-    let mut delay = dp.TIM5.delay_us(&clocks);
+    let delay = dp.TIM5.delay_us(&clocks);
 
-    let mut lcd = LCD1602::new_i2c(i2c, I2C_ADDRESS, &mut delay).expect("Init LCD failed");
+    let mut lcd = LCD1602WithDelay::new_i2c(i2c, I2C_ADDRESS, delay).expect("Init LCD failed");
 
-    let _ = lcd.reset(&mut delay);
-    let _ = lcd.clear(&mut delay);
+    let _ = lcd.reset();
+    let _ = lcd.clear();
     
-    let _ = lcd.set_display_mode(
-        DisplayMode {
-            display: Display::On,
-            cursor_visibility: Cursor::On,
-            cursor_blink: CursorBlink::On,
-        },
-        &mut delay,
-    );
-    let _ = lcd.write_str("Hello, world!", &mut delay);
+    let _ = lcd.set_display_mode(DisplayMode {
+        display: Display::On,
+        cursor_visibility: Cursor::On,
+        cursor_blink: CursorBlink::On,
+    });
+    let _ = lcd.write_str("Hello, world!");
     // Move the cursor to the second line
-    lcd.set_cursor_pos(40, &mut delay).expect("msg");
+    lcd.set_cursor(0, 1).expect("msg");
 
     // Display the following string on the second line
-    lcd.write_str("Hello Imran!", &mut delay).expect("msg");
+    lcd.write_str("Hello Imran!").expect("msg");
 
     loop {}
 }
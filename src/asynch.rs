@@ -0,0 +1,355 @@
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::i2c::I2c;
+
+use crate::command;
+use crate::data_bus::{AsyncBacklight, AsyncDataBus, AsyncI2CBus, PinMap};
+use crate::display_control::{Cursor, CursorBlink, Display, DisplayMode};
+use crate::entry_mode::{CursorMode, EntryMode, ShiftMode};
+use crate::error::{Error, Result};
+use crate::{Direction, Geometry};
+
+/**
+The async counterpart of [LCD1602](crate::LCD1602), for executors such as Embassy.
+Every method waits for the display with the async delay instead of blocking,
+and sends commands with the same encoding as the blocking driver.
+*/
+pub struct AsyncLCD1602<B: AsyncDataBus> {
+    bus: B,
+    entry_mode: EntryMode,
+    display_mode: DisplayMode,
+    geometry: Geometry,
+    // Software copy of the DDRAM address counter
+    address: u8,
+}
+
+impl<I2C: I2c> AsyncLCD1602<AsyncI2CBus<I2C>> {
+    /// Create a new instance of the `AsyncLCD1602` for a PCF8574 backpack.
+    ///
+    /// ```rust,ignore
+    /// let mut lcd = AsyncLCD1602::new_i2c(i2c, 0x27, &mut delay).await?;
+    /// ```
+    pub async fn new_i2c<D: DelayNs>(
+        i2c: I2C,
+        address: u8,
+        delay: &mut D,
+    ) -> Result<AsyncLCD1602<AsyncI2CBus<I2C>>, I2C::Error> {
+        AsyncLCD1602::new_i2c_with_pin_map(i2c, address, PinMap::default(), delay).await
+    }
+
+    /// Create a new instance of the `AsyncLCD1602` for a PCF8574 backpack wired
+    /// as described by `pin_map`.
+    pub async fn new_i2c_with_pin_map<D: DelayNs>(
+        i2c: I2C,
+        address: u8,
+        pin_map: PinMap,
+        delay: &mut D,
+    ) -> Result<AsyncLCD1602<AsyncI2CBus<I2C>>, I2C::Error> {
+        let mut hd = AsyncLCD1602 {
+            bus: AsyncI2CBus::with_pin_map(i2c, address, pin_map)?,
+            entry_mode: EntryMode::default(),
+            display_mode: DisplayMode::default(),
+            geometry: Geometry::default(),
+            address: 0,
+        };
+
+        hd.init_4bit(delay).await?;
+
+        Ok(hd)
+    }
+}
+
+impl<B: AsyncBacklight> AsyncLCD1602<B> {
+    /// Turn the backlight on or off.
+    pub async fn set_backlight(&mut self, on: bool) -> Result<(), B::Error> {
+        self.bus.set_backlight(on).await
+    }
+
+    /// Returns whether the backlight is on.
+    pub fn backlight(&self) -> bool {
+        self.bus.backlight()
+    }
+}
+
+impl<B> AsyncLCD1602<B>
+where
+    B: AsyncDataBus,
+{
+    /// Unshifts the display and sets the cursor position to 0
+    pub async fn reset<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), B::Error> {
+        self.write_command(command::RETURN_HOME, delay).await?;
+        self.address = 0;
+
+        Ok(())
+    }
+
+    /// Set if the display should be on, if the cursor should be visible, and if the cursor should blink
+    pub async fn set_display_mode<D: DelayNs>(
+        &mut self,
+        display_mode: DisplayMode,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        self.display_mode = display_mode;
+
+        let cmd = self.display_mode.as_byte();
+
+        self.write_command(cmd, delay).await
+    }
+
+    /// Clear the entire display
+    pub async fn clear<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), B::Error> {
+        self.write_command(command::CLEAR_DISPLAY, delay).await?;
+        self.address = 0;
+
+        Ok(())
+    }
+
+    /// If enabled, automatically scroll the display when a new
+    /// character is written to the display
+    pub async fn set_autoscroll<D: DelayNs>(
+        &mut self,
+        enabled: ShiftMode,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        self.entry_mode.display_shift = enabled;
+
+        let cmd = self.entry_mode.as_byte();
+
+        self.write_command(cmd, delay).await
+    }
+
+    /// Set if the cursor should be visible
+    pub async fn set_cursor_visibility<D: DelayNs>(
+        &mut self,
+        visibility: Cursor,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        self.display_mode.cursor_visibility = visibility;
+
+        let cmd = self.display_mode.as_byte();
+
+        self.write_command(cmd, delay).await
+    }
+
+    /// Set if the characters on the display should be visible
+    pub async fn set_display<D: DelayNs>(
+        &mut self,
+        display: Display,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        self.display_mode.display = display;
+
+        let cmd = self.display_mode.as_byte();
+
+        self.write_command(cmd, delay).await
+    }
+
+    /// Set if the cursor should blink
+    pub async fn set_cursor_blink<D: DelayNs>(
+        &mut self,
+        blink: CursorBlink,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        self.display_mode.cursor_blink = blink;
+
+        let cmd = self.display_mode.as_byte();
+
+        self.write_command(cmd, delay).await
+    }
+
+    /// Set which way the cursor will move when a new character is written
+    pub async fn set_cursor_mode<D: DelayNs>(
+        &mut self,
+        mode: CursorMode,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        self.entry_mode.move_direction = mode;
+
+        let cmd = self.entry_mode.as_byte();
+
+        self.write_command(cmd, delay).await
+    }
+
+    /// Set the size and DDRAM layout of the attached panel. Displays are
+    /// assumed to be 16x2 until this is called.
+    pub fn set_geometry(&mut self, geometry: Geometry) {
+        self.geometry = geometry;
+    }
+
+    /// Returns the size and DDRAM layout of the attached panel
+    pub fn geometry(&self) -> Geometry {
+        self.geometry
+    }
+
+    /// Set the cursor position to a raw DDRAM address.
+    ///
+    /// Returns an error if the address does not fit in the 7 bit address counter.
+    pub async fn set_cursor_pos<D: DelayNs>(
+        &mut self,
+        position: u8,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        if position > command::MAX_DDRAM_ADDRESS {
+            return Err(Error::OutOfBounds);
+        }
+
+        self.write_command(command::SET_DDRAM_ADDRESS | position, delay).await?;
+        self.address = position;
+
+        Ok(())
+    }
+
+    /// Move the cursor to a column and row of the display, both counted from 0.
+    ///
+    /// Returns an error if the cell is not on the display.
+    pub async fn set_cursor<D: DelayNs>(
+        &mut self,
+        column: u8,
+        row: u8,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        let position = self.geometry.address(column, row).ok_or(Error::OutOfBounds)?;
+
+        self.set_cursor_pos(position, delay).await
+    }
+
+    /// Shift just the cursor to the left or the right
+    pub async fn shift_cursor<D: DelayNs>(
+        &mut self,
+        dir: Direction,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        self.write_command(command::cursor_shift(&dir), delay).await?;
+        self.address = command::next_address(self.address, matches!(dir, Direction::Right));
+
+        Ok(())
+    }
+
+    /// Shift the entire display to the left or the right
+    pub async fn shift_display<D: DelayNs>(
+        &mut self,
+        dir: Direction,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        self.write_command(command::display_shift(&dir), delay).await
+    }
+
+    /// Write a single character. This `char` just gets downcast to a `u8`,
+    /// see [LCD1602::write_char](crate::LCD1602::write_char).
+    pub async fn write_char<D: DelayNs>(
+        &mut self,
+        data: char,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        self.write_byte(data as u8, delay).await
+    }
+
+    /// Writes a string byte-by-byte, see [LCD1602::write_str](crate::LCD1602::write_str).
+    ///
+    /// ```rust,ignore
+    /// lcd.write_str("Hello, World!", &mut delay).await?;
+    /// ```
+    pub async fn write_str<D: DelayNs>(
+        &mut self,
+        string: &str,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        self.write_bytes(string.as_bytes(), delay).await
+    }
+
+    /// Writes a sequence of bytes.
+    pub async fn write_bytes<D: DelayNs>(
+        &mut self,
+        string: &[u8],
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        for &b in string {
+            self.write_byte(b, delay).await?;
+        }
+        Ok(())
+    }
+
+    /// Writes a single byte, see [LCD1602::write_byte](crate::LCD1602::write_byte).
+    pub async fn write_byte<D: DelayNs>(
+        &mut self,
+        data: u8,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        self.bus.write(data, true, delay).await?;
+        delay.delay_us(100).await;
+
+        let forward = matches!(self.entry_mode.move_direction, CursorMode::Increment);
+        self.address = command::next_address(self.address, forward);
+
+        Ok(())
+    }
+
+    /// Creates a custom character in one of the 8 CGRAM slots, see
+    /// [LCD1602::create_char](crate::LCD1602::create_char).
+    pub async fn create_char<D: DelayNs>(
+        &mut self,
+        slot: u8,
+        glyph: &[u8; 8],
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        if slot > 7 {
+            return Err(Error::InvalidCustomCharSlot);
+        }
+
+        self.write_command(command::SET_CGRAM_ADDRESS | (slot << 3), delay).await?;
+        for &row in glyph {
+            self.bus.write(row & command::GLYPH_ROW_MASK, true, delay).await?;
+            delay.delay_us(100).await;
+        }
+
+        // Point the address counter back at DDRAM where the cursor was
+        self.set_cursor_pos(self.address, delay).await
+    }
+
+    async fn write_command<D: DelayNs>(
+        &mut self,
+        cmd: u8,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        self.bus.write(cmd, false, delay).await?;
+        delay.delay_us(command::execution_time_us(cmd)).await;
+
+        Ok(())
+    }
+
+    async fn init_4bit<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), B::Error> {
+        // Wait for the LCD to wakeup if it was off
+        delay.delay_ms(15u32).await;
+
+        // Initialize Lcd in 4-bit mode. The display still expects 8-bit
+        // transfers at this point, so these go out as single nibbles.
+        self.bus.write_nibble(command::WAKE_UP, delay).await?;
+
+        // Wait for the command to be processed
+        delay.delay_ms(5u32).await;
+
+        self.bus.write_nibble(command::WAKE_UP, delay).await?;
+        delay.delay_us(100).await;
+
+        self.bus.write_nibble(command::WAKE_UP, delay).await?;
+        delay.delay_us(100).await;
+
+        // Sets 4-bit operation
+        self.bus.write_nibble(command::FOUR_BIT_INTERFACE, delay).await?;
+        delay.delay_us(100).await;
+
+        // Two lines and 5x8 mode for chars
+        self.write_command(command::FUNCTION_SET_4BIT, delay).await?;
+
+        // Display on
+        self.write_command(command::INIT_DISPLAY_CONTROL, delay).await?;
+
+        // Clear Display
+        self.write_command(command::CLEAR_DISPLAY, delay).await?;
+
+        // Set entry mode
+        self.write_command(self.entry_mode.as_byte(), delay).await?;
+
+        // Move the cursor to beginning of first line
+        self.write_command(command::SET_DDRAM_ADDRESS, delay).await
+    }
+}
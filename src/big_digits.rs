@@ -0,0 +1,217 @@
+use embedded_hal::delay::DelayNs;
+
+use crate::data_bus::DataBus;
+use crate::error::{Error, Result};
+use crate::LCD1602;
+
+// CGRAM slots of the building blocks, shared by both fonts
+const LT: u8 = 0; // rounded upper left corner
+const UB: u8 = 1; // upper bar
+const RT: u8 = 2; // rounded upper right corner
+const LL: u8 = 3; // rounded lower left corner
+const LB: u8 = 4; // lower bar
+const LR: u8 = 5; // rounded lower right corner
+const UM: u8 = 6; // upper bar and a bar along the bottom edge
+const FULL: u8 = 0xFF;
+const SP: u8 = b' ';
+
+const BLOCKS: [[u8; 8]; 7] = [
+    [0b00111, 0b01111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111],
+    [0b11111, 0b11111, 0b11111, 0, 0, 0, 0, 0],
+    [0b11100, 0b11110, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111],
+    [0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b01111, 0b00111],
+    [0, 0, 0, 0, 0, 0b11111, 0b11111, 0b11111],
+    [0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11110, 0b11100],
+    [0b11111, 0b11111, 0b11111, 0, 0, 0, 0b11111, 0b11111],
+];
+
+/// A font of large characters built from custom characters, for showing a
+/// clock or a reading across several rows. Each character is drawn from the
+/// font's glyphs, which [LCD1602::load_big_font] stores in the first CGRAM slots.
+///
+/// The fonts have the digits `0` to `9`, `:`, `.`, `-` and space. Digits are
+/// three columns wide, `:` and `.` one column.
+#[derive(Clone, Copy, Debug)]
+pub struct BigFont {
+    height: u8,
+    glyphs: &'static [[u8; 8]],
+    // Each character as one slice of character codes per row
+    chars: &'static [(char, &'static [&'static [u8]])],
+}
+
+impl BigFont {
+    /// Characters three columns wide and two rows high, for 2-row displays.
+    /// Uses CGRAM slots 0 to 6.
+    pub const SIZE_3X2: BigFont = BigFont {
+        height: 2,
+        glyphs: &BLOCKS,
+        chars: &[
+            ('0', &[&[LT, UB, RT], &[LL, LB, LR]]),
+            ('1', &[&[UB, RT, SP], &[LB, FULL, LB]]),
+            ('2', &[&[UM, UM, RT], &[LL, LB, LB]]),
+            ('3', &[&[UM, UM, RT], &[LB, LB, LR]]),
+            ('4', &[&[LL, LB, FULL], &[SP, SP, FULL]]),
+            ('5', &[&[LT, UM, UM], &[LB, LB, LR]]),
+            ('6', &[&[LT, UM, UM], &[LL, LB, LR]]),
+            ('7', &[&[UB, UB, RT], &[SP, SP, FULL]]),
+            ('8', &[&[LT, UM, RT], &[LL, LB, LR]]),
+            ('9', &[&[LT, UM, RT], &[LB, LB, LR]]),
+            (':', &[&[LB], &[LB]]),
+            ('.', &[&[SP], &[LB]]),
+            ('-', &[&[LB, LB, LB], &[SP, SP, SP]]),
+            (' ', &[&[SP, SP, SP], &[SP, SP, SP]]),
+        ],
+    };
+
+    /// Characters three columns wide and four rows high, for 4-row displays.
+    /// Uses CGRAM slots 0 to 5.
+    pub const SIZE_3X4: BigFont = BigFont {
+        height: 4,
+        glyphs: BLOCKS.split_at(6).0,
+        chars: &[
+            ('0', &[&[LT, UB, RT], &[FULL, SP, FULL], &[FULL, SP, FULL], &[LL, LB, LR]]),
+            ('1', &[&[UB, FULL, SP], &[SP, FULL, SP], &[SP, FULL, SP], &[LB, FULL, LB]]),
+            ('2', &[&[UB, UB, RT], &[LB, LB, LR], &[LT, UB, UB], &[LL, LB, LB]]),
+            ('3', &[&[UB, UB, RT], &[LB, LB, LR], &[UB, UB, RT], &[LB, LB, LR]]),
+            ('4', &[&[FULL, SP, FULL], &[LL, LB, FULL], &[SP, SP, FULL], &[SP, SP, FULL]]),
+            ('5', &[&[LT, UB, UB], &[LL, LB, LB], &[UB, UB, RT], &[LB, LB, LR]]),
+            ('6', &[&[LT, UB, UB], &[FULL, LB, LB], &[FULL, UB, RT], &[LL, LB, LR]]),
+            ('7', &[&[UB, UB, RT], &[SP, SP, FULL], &[SP, SP, FULL], &[SP, SP, FULL]]),
+            ('8', &[&[LT, UB, RT], &[LL, LB, LR], &[LT, UB, RT], &[LL, LB, LR]]),
+            ('9', &[&[LT, UB, RT], &[LL, LB, FULL], &[SP, SP, FULL], &[LB, LB, LR]]),
+            (':', &[&[SP], &[UB], &[LB], &[SP]]),
+            ('.', &[&[SP], &[SP], &[SP], &[LB]]),
+            ('-', &[&[SP, SP, SP], &[LB, LB, LB], &[UB, UB, UB], &[SP, SP, SP]]),
+            (' ', &[&[SP, SP, SP], &[SP, SP, SP], &[SP, SP, SP], &[SP, SP, SP]]),
+        ],
+    };
+
+    /// Number of rows each character takes up.
+    pub fn height(&self) -> u8 {
+        self.height
+    }
+
+    /// Number of columns a string takes up, at most 255. Characters the font
+    /// lacks are skipped.
+    pub fn width(&self, text: &str) -> u8 {
+        text.chars()
+            .filter_map(|c| self.rows(c))
+            .fold(0u8, |width, rows| width.saturating_add(rows[0].len() as u8))
+    }
+
+    fn rows(&self, c: char) -> Option<&'static [&'static [u8]]> {
+        self.chars
+            .iter()
+            .find(|&&(glyph, _)| glyph == c)
+            .map(|&(_, rows)| rows)
+    }
+}
+
+impl<B> LCD1602<B>
+where
+    B: DataBus,
+{
+    /// Stores the glyphs of a [BigFont] in the first CGRAM slots, which
+    /// [write_big_str](#method.write_big_str) needs. This only has to be done
+    /// once, unless the slots are overwritten. The slots are kept from the
+    /// [glyph sets](#method.set_glyph_sets) until [unload_big_font](#method.unload_big_font).
+    ///
+    /// ```rust,ignore
+    /// lcd.load_big_font(&BigFont::SIZE_3X2, &mut delay)?;
+    /// ```
+    pub fn load_big_font<D: DelayNs>(
+        &mut self,
+        font: &BigFont,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        for (slot, glyph) in font.glyphs.iter().enumerate() {
+            self.create_char(slot as u8, glyph, delay)?;
+        }
+
+        Ok(())
+    }
+
+    /// Hands the CGRAM slots of a [BigFont] back to the [glyph sets](#method.set_glyph_sets),
+    /// see [release_char](#method.release_char). Big text already on the display
+    /// keeps its slots until it is overwritten.
+    pub fn unload_big_font(&mut self, font: &BigFont) {
+        for slot in 0..font.glyphs.len() {
+            self.glyph_cache.release(slot as u8);
+        }
+    }
+
+    /// Writes a string in a [BigFont] with its upper left corner at a column and
+    /// row of the display. Characters follow each other without a gap, and
+    /// characters the font lacks are skipped.
+    ///
+    /// Returns an error if the text does not fit on the display. The cursor is
+    /// left after the last cell of the bottom row.
+    ///
+    /// ```rust,ignore
+    /// lcd.load_big_font(&BigFont::SIZE_3X2, &mut delay)?;
+    /// lcd.write_big_str(&BigFont::SIZE_3X2, "12:45", 1, 0, &mut delay)?;
+    /// ```
+    pub fn write_big_str<D: DelayNs>(
+        &mut self,
+        font: &BigFont,
+        text: &str,
+        column: u8,
+        row: u8,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        let width = font.width(text);
+        if column as u16 + width as u16 > self.geometry.columns() as u16
+            || row as u16 + font.height as u16 > self.geometry.rows() as u16
+        {
+            return Err(Error::OutOfBounds);
+        }
+
+        for line in 0..font.height {
+            self.set_cursor(column, row + line, delay)?;
+
+            for rows in text.chars().filter_map(|c| font.rows(c)) {
+                self.write_bytes(rows[line as usize], delay)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_bus::NoDelay;
+    use crate::{Geometry, GlyphSet, RomA00};
+
+    #[test]
+    fn width_saturates() {
+        let text = core::str::from_utf8(&[b'8'; 100]).unwrap();
+
+        assert_eq!(BigFont::SIZE_3X2.width("12:45"), 13);
+        assert_eq!(BigFont::SIZE_3X2.width(text), 255);
+
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        assert_eq!(
+            lcd.write_big_str(&BigFont::SIZE_3X2, text, 0, 0, &mut NoDelay),
+            Err(Error::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn unload_frees_slots() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        lcd.set_character_rom(&RomA00);
+        lcd.set_glyph_sets(&[GlyphSet::CYRILLIC]);
+        lcd.set_fallback_char(b'?');
+        lcd.load_big_font(&BigFont::SIZE_3X2, &mut NoDelay).unwrap();
+
+        // Only slot 7 is left, and it is still shown when Д is written
+        lcd.write_str("БД", &mut NoDelay).unwrap();
+        assert_eq!(&lcd.bus().ddram[..2], &[7, b'?']);
+
+        lcd.unload_big_font(&BigFont::SIZE_3X2);
+        lcd.write_str("Д", &mut NoDelay).unwrap();
+        assert_eq!(lcd.bus().ddram[2], 0);
+    }
+}
@@ -0,0 +1,348 @@
+use embedded_hal::delay::DelayNs;
+
+use crate::data_bus::DataBus;
+use crate::error::Result;
+use crate::LCD1602;
+
+/// A trait for the character generator ROM of a display, which decides the
+/// glyph shown for each character code.
+///
+/// HD44780 compatible controllers ship with one of two ROMs. Which one a display
+/// has is usually printed on the controller as a suffix, e.g. `HD44780UA00`, and
+/// can otherwise be told by writing `0xE4`: the A00 ROM shows `µ` and the A02 ROM
+/// shows `ä`.
+pub trait CharacterRom: Sync {
+    /// Returns the character code of the glyph showing `c`, or `None` if the ROM
+    /// has no such glyph.
+    fn encode(&self, c: char) -> Option<u8>;
+
+    /// Returns the code of a mark written after the glyph of `c`, for characters
+    /// the ROM shows as a base glyph followed by a mark, such as voiced katakana
+    /// on the A00 ROM. Defaults to `None`.
+    fn mark(&self, c: char) -> Option<u8> {
+        let _ = c;
+        None
+    }
+}
+
+/// The A00 ROM: ASCII, Japanese katakana and a few Greek and math symbols.
+/// `\` and `~` are replaced by `¥` and `→`.
+///
+/// The ROM only has half-width katakana. Full-width katakana are shown as their
+/// half-width forms, with voiced ones such as `ガ` written as the base kana
+/// followed by `ﾞ` or `ﾟ`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RomA00;
+
+/// The A02 ROM: full ASCII, Latin-1, and some Cyrillic, Greek and other symbols.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RomA02;
+
+// Glyphs outside the ASCII and katakana ranges. Some characters map to the
+// glyph they look like, such as `ß` to `β` and `°` to the handakuten.
+const A00_SYMBOLS: &[(char, u8)] = &[
+    ('¥', 0x5C),
+    ('→', 0x7E),
+    ('←', 0x7F),
+    ('。', 0xA1),
+    ('「', 0xA2),
+    ('」', 0xA3),
+    ('、', 0xA4),
+    ('・', 0xA5),
+    ('·', 0xA5),
+    ('ー', 0xB0),
+    ('゛', 0xDE),
+    ('゜', 0xDF),
+    ('°', 0xDF),
+    ('α', 0xE0),
+    ('ä', 0xE1),
+    ('β', 0xE2),
+    ('ß', 0xE2),
+    ('ε', 0xE3),
+    ('μ', 0xE4),
+    ('µ', 0xE4),
+    ('σ', 0xE5),
+    ('ρ', 0xE6),
+    ('√', 0xE8),
+    ('¢', 0xEC),
+    ('£', 0xED),
+    ('ñ', 0xEE),
+    ('ö', 0xEF),
+    ('θ', 0xF2),
+    ('∞', 0xF3),
+    ('Ω', 0xF4),
+    ('Ω', 0xF4),
+    ('ü', 0xF5),
+    ('Σ', 0xF6),
+    ('π', 0xF7),
+    ('千', 0xFA),
+    ('万', 0xFB),
+    ('円', 0xFC),
+    ('÷', 0xFD),
+    ('█', 0xFF),
+];
+
+const DAKUTEN: u8 = 0xDE;
+const HANDAKUTEN: u8 = 0xDF;
+
+// Half-width codes of the full-width katakana from U+30A1 to U+30FA. Small kana
+// without a half-width form use the full size one, and voiced kana the unvoiced
+// one, the mark is added by `RomA00::mark`.
+const A00_KATAKANA: [u8; 90] = [
+    0xA7, 0xB1, 0xA8, 0xB2, 0xA9, 0xB3, 0xAA, 0xB4, 0xAB, 0xB5, // ァ..オ
+    0xB6, 0xB6, 0xB7, 0xB7, 0xB8, 0xB8, 0xB9, 0xB9, 0xBA, 0xBA, // カ..ゴ
+    0xBB, 0xBB, 0xBC, 0xBC, 0xBD, 0xBD, 0xBE, 0xBE, 0xBF, 0xBF, // サ..ゾ
+    0xC0, 0xC0, 0xC1, 0xC1, 0xAF, 0xC2, 0xC2, 0xC3, 0xC3, 0xC4, // タ..ト
+    0xC4, 0xC5, 0xC6, 0xC7, 0xC8, 0xC9, 0xCA, 0xCA, 0xCA, 0xCB, // ド..ヒ
+    0xCB, 0xCB, 0xCC, 0xCC, 0xCC, 0xCD, 0xCD, 0xCD, 0xCE, 0xCE, // ビ..ボ
+    0xCE, 0xCF, 0xD0, 0xD1, 0xD2, 0xD3, 0xAC, 0xD4, 0xAD, 0xD5, // ポ..ユ
+    0xAE, 0xD6, 0xD7, 0xD8, 0xD9, 0xDA, 0xDB, 0xDC, 0xDC, 0xB2, // ョ..ヰ
+    0xB4, 0xA6, 0xDD, 0xB3, 0xB6, 0xB9, 0xDC, 0xB2, 0xB4, 0xA6, // ヱ..ヺ
+];
+
+// Glyphs outside the ASCII and Latin-1 ranges
+const A02_SYMBOLS: &[(char, u8)] = &[
+    ('▶', 0x10),
+    ('◀', 0x11),
+    ('“', 0x12),
+    ('”', 0x13),
+    ('●', 0x16),
+    ('↵', 0x17),
+    ('↑', 0x18),
+    ('↓', 0x19),
+    ('→', 0x1A),
+    ('←', 0x1B),
+    ('≤', 0x1C),
+    ('≥', 0x1D),
+    ('▲', 0x1E),
+    ('▼', 0x1F),
+    ('⌂', 0x7F),
+    ('Б', 0x80),
+    ('Д', 0x81),
+    ('Ж', 0x82),
+    ('З', 0x83),
+    ('И', 0x84),
+    ('Й', 0x85),
+    ('Л', 0x86),
+    ('П', 0x87),
+    ('У', 0x88),
+    ('Ц', 0x89),
+    ('Ч', 0x8A),
+    ('Ш', 0x8B),
+    ('Щ', 0x8C),
+    ('Ъ', 0x8D),
+    ('Ы', 0x8E),
+    ('Э', 0x8F),
+    ('α', 0x90),
+    ('♪', 0x91),
+    ('Γ', 0x92),
+    ('π', 0x93),
+    ('Σ', 0x94),
+    ('σ', 0x95),
+    ('♬', 0x96),
+    ('τ', 0x97),
+    ('🔔', 0x98),
+    ('Θ', 0x99),
+    ('Ω', 0x9A),
+    ('Ω', 0x9A),
+    ('δ', 0x9B),
+    ('∞', 0x9C),
+    ('♥', 0x9D),
+    ('ε', 0x9E),
+    ('∩', 0x9F),
+    ('‖', 0xA0),
+    ('¡', 0xA1),
+    ('¢', 0xA2),
+    ('£', 0xA3),
+    ('¤', 0xA4),
+    ('¥', 0xA5),
+    ('¦', 0xA6),
+    ('§', 0xA7),
+    ('ƒ', 0xA8),
+    ('©', 0xA9),
+    ('ª', 0xAA),
+    ('«', 0xAB),
+    ('Ю', 0xAC),
+    ('Я', 0xAD),
+    ('®', 0xAE),
+    ('‘', 0xAF),
+    ('°', 0xB0),
+    ('±', 0xB1),
+    ('²', 0xB2),
+    ('³', 0xB3),
+    ('₧', 0xB4),
+    ('µ', 0xB5),
+    ('μ', 0xB5),
+    ('¶', 0xB6),
+    ('·', 0xB7),
+    ('ω', 0xB8),
+    ('¹', 0xB9),
+    ('º', 0xBA),
+    ('»', 0xBB),
+    ('¼', 0xBC),
+    ('½', 0xBD),
+    ('¾', 0xBE),
+    ('¿', 0xBF),
+];
+
+fn lookup(symbols: &[(char, u8)], c: char) -> Option<u8> {
+    symbols
+        .iter()
+        .find(|&&(symbol, _)| symbol == c)
+        .map(|&(_, code)| code)
+}
+
+impl CharacterRom for RomA00 {
+    fn encode(&self, c: char) -> Option<u8> {
+        match c {
+            ' '..='}' if c != '\\' => Some(c as u8),
+            // Half-width katakana are in the same order as in JIS X 0201
+            '\u{FF61}'..='\u{FF9F}' => Some((c as u32 - 0xFF61 + 0xA1) as u8),
+            'ァ'..='ヺ' => Some(A00_KATAKANA[(c as u32 - 'ァ' as u32) as usize]),
+            _ => lookup(A00_SYMBOLS, c),
+        }
+    }
+
+    fn mark(&self, c: char) -> Option<u8> {
+        match c {
+            'ガ' | 'ギ' | 'グ' | 'ゲ' | 'ゴ' | 'ザ' | 'ジ' | 'ズ' | 'ゼ' | 'ゾ' | 'ダ' | 'ヂ'
+            | 'ヅ' | 'デ' | 'ド' | 'バ' | 'ビ' | 'ブ' | 'ベ' | 'ボ' | 'ヴ' | 'ヷ' | 'ヸ' | 'ヹ'
+            | 'ヺ' => Some(DAKUTEN),
+            'パ' | 'ピ' | 'プ' | 'ペ' | 'ポ' => Some(HANDAKUTEN),
+            _ => None,
+        }
+    }
+}
+
+impl CharacterRom for RomA02 {
+    fn encode(&self, c: char) -> Option<u8> {
+        match c {
+            ' '..='~' | 'À'..='ÿ' => Some(c as u8),
+            _ => lookup(A02_SYMBOLS, c),
+        }
+    }
+}
+
+impl<B> LCD1602<B>
+where
+    B: DataBus,
+{
+    /// Set the character ROM of the display. [write_str](#method.write_str),
+    /// [write_char](#method.write_char) and the [writer](#method.writer) then
+    /// convert each character to the code of its glyph in that ROM, and write the
+    /// [fallback character](#method.set_fallback_char) for characters it lacks.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_character_rom(&RomA02);
+    /// lcd.write_str("Grüße, 20°C", &mut delay)?;
+    /// ```
+    pub fn set_character_rom(&mut self, rom: &'static dyn CharacterRom) {
+        self.rom = Some(rom);
+    }
+
+    /// Set the code written for characters the [character ROM](#method.set_character_rom)
+    /// has no glyph for. Defaults to `?`, a custom character slot works as well.
+    pub fn set_fallback_char(&mut self, code: u8) {
+        self.fallback = code;
+    }
+
+    // The code to write for a character: its ROM code, or a glyph loaded into
+    // CGRAM, or the fallback. Without a ROM anything but ASCII is downcast.
+    pub(crate) fn encode<D: DelayNs>(&mut self, c: char, delay: &mut D) -> Result<u8, B::Error> {
+        let code = match self.rom {
+            Some(rom) => rom.encode(c),
+            None => c.is_ascii().then_some(c as u8),
+        };
+
+        if let Some(code) = code {
+            return Ok(code);
+        }
+
+        if let Some(code) = self.glyph_code(c, delay)? {
+            return Ok(code);
+        }
+
+        Ok(match self.rom {
+            Some(_) => self.fallback,
+            None => c as u8,
+        })
+    }
+
+    // The code of the mark the ROM writes after a character, see CharacterRom::mark
+    pub(crate) fn mark(&self, c: char) -> Option<u8> {
+        self.rom.and_then(|rom| rom.mark(c))
+    }
+
+    // Encode text into cells, with the mark of a character in the cell after
+    // it. Returns the number of cells used, text that does not fit is dropped.
+    pub(crate) fn encode_cells<D: DelayNs>(
+        &mut self,
+        text: impl IntoIterator<Item = char>,
+        cells: &mut [u8],
+        delay: &mut D,
+    ) -> Result<usize, B::Error> {
+        let mut len = 0;
+
+        for c in text {
+            if len == cells.len() {
+                break;
+            }
+            cells[len] = self.encode(c, delay)?;
+            len += 1;
+
+            if let Some(mark) = self.mark(c) {
+                if len == cells.len() {
+                    break;
+                }
+                cells[len] = mark;
+                len += 1;
+            }
+        }
+
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_bus::NoDelay;
+    use crate::Geometry;
+
+    #[test]
+    fn full_width_katakana() {
+        let full = "アイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨ\
+                    ラリルレロワヲンァィゥェォャュョッー";
+        let half = "ｱｲｳｴｵｶｷｸｹｺｻｼｽｾｿﾀﾁﾂﾃﾄﾅﾆﾇﾈﾉﾊﾋﾌﾍﾎﾏﾐﾑﾒﾓﾔﾕﾖﾗﾘﾙﾚﾛﾜｦﾝｧｨｩｪｫｬｭｮｯｰ";
+
+        assert_eq!(full.chars().count(), half.chars().count());
+        for (full, half) in full.chars().zip(half.chars()) {
+            assert_eq!(RomA00.encode(full), RomA00.encode(half), "{full}");
+            assert_eq!(RomA00.mark(full), None, "{full}");
+        }
+    }
+
+    #[test]
+    fn voiced_katakana() {
+        assert_eq!(RomA00.encode('ガ'), RomA00.encode('カ'));
+        assert_eq!(RomA00.mark('ガ'), Some(DAKUTEN));
+        assert_eq!(RomA00.encode('ポ'), RomA00.encode('ホ'));
+        assert_eq!(RomA00.mark('ポ'), Some(HANDAKUTEN));
+        assert_eq!(RomA00.encode('ヴ'), RomA00.encode('ウ'));
+        assert_eq!(RomA00.mark('ヴ'), Some(DAKUTEN));
+        assert_eq!(RomA02.mark('ガ'), None);
+    }
+
+    #[test]
+    fn write_str_adds_marks() {
+        let geometry = Geometry::LCD16X2;
+        let mut lcd = LCD1602::test(geometry);
+        lcd.set_character_rom(&RomA00);
+        lcd.write_str("ゲーム", &mut NoDelay).unwrap();
+
+        assert_eq!(
+            &lcd.bus().row(geometry, 0)[..5],
+            &[0xB9, 0xDE, 0xB0, 0xD1, b' ']
+        );
+    }
+}
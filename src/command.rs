@@ -0,0 +1,115 @@
+// Instruction encoding shared by the blocking and the async driver
+
+use crate::data_bus::Controller;
+use crate::Direction;
+
+pub(crate) const CLEAR_DISPLAY: u8 = 0b0000_0001;
+pub(crate) const RETURN_HOME: u8 = 0b0000_0010;
+pub(crate) const SET_CGRAM_ADDRESS: u8 = 0b0100_0000;
+pub(crate) const SET_DDRAM_ADDRESS: u8 = 0b1000_0000;
+
+// Sent as single nibbles while waking up a display on a 4-bit bus
+pub(crate) const WAKE_UP: u8 = 0b0011_0000;
+pub(crate) const FOUR_BIT_INTERFACE: u8 = 0b0010_0000;
+
+// Function set: interface width, two lines and the 5x8 font
+pub(crate) const FUNCTION_SET_4BIT: u8 = 0b0010_1000;
+pub(crate) const FUNCTION_SET_8BIT: u8 = 0b0011_1000;
+// Line and font bits of the function set. The font bit is ignored in two line
+// mode, so the 5x10 font comes with one line.
+pub(crate) const LINES_AND_FONT: u8 = 0b0000_1100;
+pub(crate) const TWO_LINES_5X8: u8 = 0b0000_1000;
+pub(crate) const ONE_LINE_5X10: u8 = 0b0000_0100;
+
+// Display on with a steady cursor, the state the display is brought up in
+pub(crate) const INIT_DISPLAY_CONTROL: u8 = 0b0000_1110;
+
+// Highest DDRAM address the 7 bit address counter can hold
+pub(crate) const MAX_DDRAM_ADDRESS: u8 = 0b0111_1111;
+// Cursor and blink bits of the display control instruction
+pub(crate) const CURSOR_BITS: u8 = 0b0000_0011;
+// Custom characters only use the lower five bits of each row
+pub(crate) const GLYPH_ROW_MASK: u8 = 0b0001_1111;
+
+// Time to wait for an instruction when the busy flag cannot be read. Clear
+// display and return home take 1.52 ms, everything else 37 µs.
+pub(crate) fn execution_time_us(cmd: u8) -> u32 {
+    if cmd & 0b1111_1100 == 0 {
+        1_520
+    } else {
+        100
+    }
+}
+
+pub(crate) fn cursor_shift(dir: &Direction) -> u8 {
+    0b0001_0000 | shift_direction(dir)
+}
+
+pub(crate) fn display_shift(dir: &Direction) -> u8 {
+    0b0001_1000 | shift_direction(dir)
+}
+
+fn shift_direction(dir: &Direction) -> u8 {
+    match dir {
+        Direction::Left => 0b0000_0000,
+        Direction::Right => 0b0000_0100,
+    }
+}
+
+// Step a DDRAM address the way the address counter does in 2-line mode, where
+// line 1 covers 0x00..=0x27 and line 2 covers 0x40..=0x67. Bit 7 is not part
+// of the counter, it marks the second controller of a 40x4 display.
+pub(crate) fn next_address(address: u8, forward: bool) -> u8 {
+    let controller = address & !MAX_DDRAM_ADDRESS;
+
+    controller
+        | match (address & MAX_DDRAM_ADDRESS, forward) {
+            (0x27, true) => 0x40,
+            (0x67, true) => 0x00,
+            (0x00, false) => 0x67,
+            (0x40, false) => 0x27,
+            (address, true) => address.wrapping_add(1) & MAX_DDRAM_ADDRESS,
+            (address, false) => address.wrapping_sub(1) & MAX_DDRAM_ADDRESS,
+        }
+}
+
+// Controller an address belongs to, see `next_address`
+pub(crate) fn controller(address: u8) -> Controller {
+    if address & !MAX_DDRAM_ADDRESS != 0 {
+        Controller::Second
+    } else {
+        Controller::First
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_address_wraps_between_lines() {
+        assert_eq!(next_address(0x00, true), 0x01);
+        assert_eq!(next_address(0x27, true), 0x40);
+        assert_eq!(next_address(0x67, true), 0x00);
+        assert_eq!(next_address(0x40, false), 0x27);
+        assert_eq!(next_address(0x00, false), 0x67);
+        assert_eq!(next_address(0x41, false), 0x40);
+    }
+
+    #[test]
+    fn next_address_keeps_controller() {
+        assert_eq!(next_address(0x80, true), 0x81);
+        assert_eq!(next_address(0xA7, true), 0xC0);
+        assert_eq!(next_address(0xE7, true), 0x80);
+        assert_eq!(next_address(0x80, false), 0xE7);
+        assert_eq!(next_address(0xC0, false), 0xA7);
+    }
+
+    #[test]
+    fn controller_of_address() {
+        assert_eq!(controller(0x00), Controller::First);
+        assert_eq!(controller(0x67), Controller::First);
+        assert_eq!(controller(0x80), Controller::Second);
+        assert_eq!(controller(0xE7), Controller::Second);
+    }
+}
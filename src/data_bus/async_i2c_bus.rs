@@ -0,0 +1,108 @@
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::i2c::I2c;
+use crate::{
+    data_bus::{AsyncBacklight, AsyncDataBus, PinMap},
+    error::{Error, Result},
+};
+
+/// A struct for I2C bus communication on top of `embedded-hal-async`.
+///
+/// Each enable pulse is sent as part of a single I2C transfer, so no sleeping
+/// is done between nibbles and the executor is free while the bytes go out.
+pub struct AsyncI2CBus<I2C: I2c> {
+    i2c_bus: I2C,
+    address: u8,
+    pin_map: PinMap,
+    backlight: bool,
+}
+
+impl<I2C: I2c> AsyncI2CBus<I2C> {
+    /// Creates a new `AsyncI2CBus` instance for a backpack using the
+    /// [generic](PinMap::GENERIC) pin mapping.
+    pub fn new(i2c_bus: I2C, address: u8) -> AsyncI2CBus<I2C> {
+        AsyncI2CBus {
+            i2c_bus,
+            address,
+            pin_map: PinMap::default(),
+            backlight: true,
+        }
+    }
+
+    /// Creates a new `AsyncI2CBus` instance for a backpack wired as described by `pin_map`.
+    ///
+    /// Returns an error if the mapping uses a pin past P7.
+    pub fn with_pin_map(
+        i2c_bus: I2C,
+        address: u8,
+        pin_map: PinMap,
+    ) -> Result<AsyncI2CBus<I2C>, I2C::Error> {
+        pin_map.check(8)?;
+
+        Ok(AsyncI2CBus {
+            i2c_bus,
+            address,
+            pin_map,
+            backlight: true,
+        })
+    }
+
+    fn port_byte(&self, nibble: u8, rs: bool, en: bool) -> u8 {
+        self.pin_map.port_bits(nibble, rs, false, en, self.backlight) as u8
+    }
+
+    // Each nibble is latched by raising and dropping enable, the port is
+    // updated after every byte of the transfer
+    fn pulse(&self, nibble: u8, data: bool) -> [u8; 3] {
+        let byte = self.port_byte(nibble, data, false);
+
+        [byte, self.port_byte(nibble, data, true), byte]
+    }
+}
+
+impl<I2C: I2c> AsyncDataBus for AsyncI2CBus<I2C> {
+    type Error = I2C::Error;
+
+    async fn write<D: DelayNs>(
+        &mut self,
+        byte: u8,
+        data: bool,
+        _delay: &mut D,
+    ) -> Result<(), I2C::Error> {
+        let [a, b, c] = self.pulse(byte >> 4, data);
+        let [d, e, f] = self.pulse(byte & 0x0F, data);
+
+        self.i2c_bus
+            .write(self.address, &[a, b, c, d, e, f])
+            .await
+            .map_err(Error::from_i2c)
+    }
+
+    async fn write_nibble<D: DelayNs>(
+        &mut self,
+        nibble: u8,
+        _delay: &mut D,
+    ) -> Result<(), I2C::Error> {
+        let bytes = self.pulse(nibble >> 4, false);
+
+        self.i2c_bus
+            .write(self.address, &bytes)
+            .await
+            .map_err(Error::from_i2c)
+    }
+}
+
+impl<I2C: I2c> AsyncBacklight for AsyncI2CBus<I2C> {
+    async fn set_backlight(&mut self, on: bool) -> Result<(), I2C::Error> {
+        self.backlight = on;
+
+        let byte = self.port_byte(0, false, false);
+        self.i2c_bus
+            .write(self.address, &[byte])
+            .await
+            .map_err(Error::from_i2c)
+    }
+
+    fn backlight(&self) -> bool {
+        self.backlight
+    }
+}
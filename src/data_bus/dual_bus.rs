@@ -0,0 +1,261 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{ErrorKind, OutputPin};
+
+use crate::data_bus::{set_pin, Controller, DataBus};
+use crate::error::{Error, Result};
+
+// Pulse the enable lines of the selected controllers together
+fn pulse_enable<EN1: OutputPin, EN2: OutputPin, D: DelayNs>(
+    en1: &mut EN1,
+    en2: &mut EN2,
+    selected: Controller,
+    delay: &mut D,
+) -> Result<(), ErrorKind> {
+    let first = selected != Controller::Second;
+    let second = selected != Controller::First;
+
+    set_pin(en1, first)?;
+    set_pin(en2, second)?;
+    delay.delay_us(1);
+    en1.set_low().map_err(Error::from_pin)?;
+    en2.set_low().map_err(Error::from_pin)?;
+    delay.delay_us(1);
+
+    Ok(())
+}
+
+/// A struct for 4-bit bus communication with a display made of two controllers,
+/// such as 40x4 panels. The controllers share the register select and data pins
+/// and each has its own enable pin.
+///
+/// Transfers go to both controllers until [DataBus::select_controller] picks one.
+pub struct FourBitDualBus<
+    RS: OutputPin,
+    EN1: OutputPin,
+    EN2: OutputPin,
+    D4: OutputPin,
+    D5: OutputPin,
+    D6: OutputPin,
+    D7: OutputPin,
+> {
+    rs: RS,
+    en1: EN1,
+    en2: EN2,
+    d4: D4,
+    d5: D5,
+    d6: D6,
+    d7: D7,
+    selected: Controller,
+}
+
+impl<
+        RS: OutputPin,
+        EN1: OutputPin,
+        EN2: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+    > FourBitDualBus<RS, EN1, EN2, D4, D5, D6, D7>
+{
+    /// Creates a new `FourBitDualBus` instance. `en1` is the enable pin of the
+    /// upper two rows, `en2` of the lower two.
+    pub fn from_pins(
+        rs: RS,
+        en1: EN1,
+        en2: EN2,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+    ) -> FourBitDualBus<RS, EN1, EN2, D4, D5, D6, D7> {
+        FourBitDualBus {
+            rs,
+            en1,
+            en2,
+            d4,
+            d5,
+            d6,
+            d7,
+            selected: Controller::Both,
+        }
+    }
+
+    // Put the lower four bits of `nibble` on d4..d7
+    fn set_nibble(&mut self, nibble: u8) -> Result<(), ErrorKind> {
+        set_pin(&mut self.d4, nibble & 0b0001 != 0)?;
+        set_pin(&mut self.d5, nibble & 0b0010 != 0)?;
+        set_pin(&mut self.d6, nibble & 0b0100 != 0)?;
+        set_pin(&mut self.d7, nibble & 0b1000 != 0)?;
+
+        Ok(())
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN1: OutputPin,
+        EN2: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+    > DataBus for FourBitDualBus<RS, EN1, EN2, D4, D5, D6, D7>
+{
+    type Error = ErrorKind;
+
+    fn write<D: DelayNs>(
+        &mut self,
+        byte: u8,
+        data: bool,
+        delay: &mut D,
+    ) -> Result<(), ErrorKind> {
+        set_pin(&mut self.rs, data)?;
+
+        self.set_nibble(byte >> 4)?;
+        pulse_enable(&mut self.en1, &mut self.en2, self.selected, delay)?;
+
+        self.set_nibble(byte & 0x0F)?;
+        pulse_enable(&mut self.en1, &mut self.en2, self.selected, delay)
+    }
+
+    fn write_nibble<D: DelayNs>(&mut self, nibble: u8, delay: &mut D) -> Result<(), ErrorKind> {
+        self.rs.set_low().map_err(Error::from_pin)?;
+
+        self.set_nibble(nibble >> 4)?;
+        pulse_enable(&mut self.en1, &mut self.en2, self.selected, delay)
+    }
+
+    fn select_controller(&mut self, controller: Controller) -> Result<(), ErrorKind> {
+        self.selected = controller;
+
+        Ok(())
+    }
+}
+
+/// A struct for 8-bit bus communication with a display made of two controllers,
+/// such as 40x4 panels. The controllers share the register select and data pins
+/// and each has its own enable pin.
+///
+/// Transfers go to both controllers until [DataBus::select_controller] picks one.
+pub struct EightBitDualBus<
+    RS: OutputPin,
+    EN1: OutputPin,
+    EN2: OutputPin,
+    D0: OutputPin,
+    D1: OutputPin,
+    D2: OutputPin,
+    D3: OutputPin,
+    D4: OutputPin,
+    D5: OutputPin,
+    D6: OutputPin,
+    D7: OutputPin,
+> {
+    rs: RS,
+    en1: EN1,
+    en2: EN2,
+    d0: D0,
+    d1: D1,
+    d2: D2,
+    d3: D3,
+    d4: D4,
+    d5: D5,
+    d6: D6,
+    d7: D7,
+    selected: Controller,
+}
+
+impl<
+        RS: OutputPin,
+        EN1: OutputPin,
+        EN2: OutputPin,
+        D0: OutputPin,
+        D1: OutputPin,
+        D2: OutputPin,
+        D3: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+    > EightBitDualBus<RS, EN1, EN2, D0, D1, D2, D3, D4, D5, D6, D7>
+{
+    /// Creates a new `EightBitDualBus` instance. `en1` is the enable pin of the
+    /// upper two rows, `en2` of the lower two.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_pins(
+        rs: RS,
+        en1: EN1,
+        en2: EN2,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+    ) -> EightBitDualBus<RS, EN1, EN2, D0, D1, D2, D3, D4, D5, D6, D7> {
+        EightBitDualBus {
+            rs,
+            en1,
+            en2,
+            d0,
+            d1,
+            d2,
+            d3,
+            d4,
+            d5,
+            d6,
+            d7,
+            selected: Controller::Both,
+        }
+    }
+
+    fn set_bus_bits(&mut self, data: u8) -> Result<(), ErrorKind> {
+        set_pin(&mut self.d0, data & 0b0000_0001 != 0)?;
+        set_pin(&mut self.d1, data & 0b0000_0010 != 0)?;
+        set_pin(&mut self.d2, data & 0b0000_0100 != 0)?;
+        set_pin(&mut self.d3, data & 0b0000_1000 != 0)?;
+        set_pin(&mut self.d4, data & 0b0001_0000 != 0)?;
+        set_pin(&mut self.d5, data & 0b0010_0000 != 0)?;
+        set_pin(&mut self.d6, data & 0b0100_0000 != 0)?;
+        set_pin(&mut self.d7, data & 0b1000_0000 != 0)?;
+
+        Ok(())
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN1: OutputPin,
+        EN2: OutputPin,
+        D0: OutputPin,
+        D1: OutputPin,
+        D2: OutputPin,
+        D3: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+    > DataBus for EightBitDualBus<RS, EN1, EN2, D0, D1, D2, D3, D4, D5, D6, D7>
+{
+    type Error = ErrorKind;
+
+    fn write<D: DelayNs>(
+        &mut self,
+        byte: u8,
+        data: bool,
+        delay: &mut D,
+    ) -> Result<(), ErrorKind> {
+        set_pin(&mut self.rs, data)?;
+
+        self.set_bus_bits(byte)?;
+        pulse_enable(&mut self.en1, &mut self.en2, self.selected, delay)
+    }
+
+    fn select_controller(&mut self, controller: Controller) -> Result<(), ErrorKind> {
+        self.selected = controller;
+
+        Ok(())
+    }
+}
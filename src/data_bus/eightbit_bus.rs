@@ -1,5 +1,5 @@
 use embedded_hal::delay::DelayNs;
-use embedded_hal::digital::OutputPin;
+use embedded_hal::digital::{ErrorKind, OutputPin};
 use crate::{
     data_bus::DataBus,
     error::{Error, Result},
@@ -44,6 +44,7 @@ impl<
     > EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>
 {
     /// Creates a new `EightBitBus` instance.
+    #[allow(clippy::too_many_arguments)]
     pub fn from_pins(
         rs: RS,
         en: EN,
@@ -70,7 +71,7 @@ impl<
         }
     }
 
-    fn set_bus_bits(&mut self, data: u8) -> Result<()> {
+    fn set_bus_bits(&mut self, data: u8) -> Result<(), ErrorKind> {
         let db0: bool = (0b0000_0001 & data) != 0;
         let db1: bool = (0b0000_0010 & data) != 0;
         let db2: bool = (0b0000_0100 & data) != 0;
@@ -81,51 +82,51 @@ impl<
         let db7: bool = (0b1000_0000 & data) != 0;
 
         if db0 {
-            self.d0.set_high().map_err(|_| Error)?;
+            self.d0.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d0.set_low().map_err(|_| Error)?;
+            self.d0.set_low().map_err(Error::from_pin)?;
         }
 
         if db1 {
-            self.d1.set_high().map_err(|_| Error)?;
+            self.d1.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d1.set_low().map_err(|_| Error)?;
+            self.d1.set_low().map_err(Error::from_pin)?;
         }
 
         if db2 {
-            self.d2.set_high().map_err(|_| Error)?;
+            self.d2.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d2.set_low().map_err(|_| Error)?;
+            self.d2.set_low().map_err(Error::from_pin)?;
         }
 
         if db3 {
-            self.d3.set_high().map_err(|_| Error)?;
+            self.d3.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d3.set_low().map_err(|_| Error)?;
+            self.d3.set_low().map_err(Error::from_pin)?;
         }
 
         if db4 {
-            self.d4.set_high().map_err(|_| Error)?;
+            self.d4.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d4.set_low().map_err(|_| Error)?;
+            self.d4.set_low().map_err(Error::from_pin)?;
         }
 
         if db5 {
-            self.d5.set_high().map_err(|_| Error)?;
+            self.d5.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d5.set_low().map_err(|_| Error)?;
+            self.d5.set_low().map_err(Error::from_pin)?;
         }
 
         if db6 {
-            self.d6.set_high().map_err(|_| Error)?;
+            self.d6.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d6.set_low().map_err(|_| Error)?;
+            self.d6.set_low().map_err(Error::from_pin)?;
         }
 
         if db7 {
-            self.d7.set_high().map_err(|_| Error)?;
+            self.d7.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d7.set_low().map_err(|_| Error)?;
+            self.d7.set_low().map_err(Error::from_pin)?;
         }
 
         Ok(())
@@ -145,26 +146,28 @@ impl<
         D7: OutputPin,
     > DataBus for EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>
 {
+    type Error = ErrorKind;
+
     fn write<D: DelayNs>(
         &mut self,
         byte: u8,
         data: bool,
         delay: &mut D,
-    ) -> Result<()> {
+    ) -> Result<(), ErrorKind> {
         if data {
-            self.rs.set_high().map_err(|_| Error)?;
+            self.rs.set_high().map_err(Error::from_pin)?;
         } else {
-            self.rs.set_low().map_err(|_| Error)?;
+            self.rs.set_low().map_err(Error::from_pin)?;
         }
 
         self.set_bus_bits(byte)?;
 
-        self.en.set_high().map_err(|_| Error)?;
+        self.en.set_high().map_err(Error::from_pin)?;
         delay.delay_ms(2u32);
-        self.en.set_low().map_err(|_| Error)?;
+        self.en.set_low().map_err(Error::from_pin)?;
 
         if data {
-            self.rs.set_low().map_err(|_| Error)?;
+            self.rs.set_low().map_err(Error::from_pin)?;
         }
 
         Ok(())
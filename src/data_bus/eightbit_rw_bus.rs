@@ -0,0 +1,186 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{ErrorKind, InputPin, OutputPin};
+
+use crate::data_bus::{is_high, set_pin, DataBus, BUSY_FLAG, BUSY_POLL_INTERVAL_US, BUSY_TIMEOUT_US};
+use crate::error::{Error, Result};
+
+/// A struct for 8-bit bus communication with a read/write pin.
+///
+/// The data pins are read back to poll the busy flag, so they need to be able to
+/// act as inputs as well as outputs. Open-drain pins with pull-ups work, as does
+/// any pin type that implements both traits and releases the line when set high.
+pub struct EightBitRwBus<
+    RS: OutputPin,
+    RW: OutputPin,
+    EN: OutputPin,
+    D0: InputPin + OutputPin,
+    D1: InputPin + OutputPin,
+    D2: InputPin + OutputPin,
+    D3: InputPin + OutputPin,
+    D4: InputPin + OutputPin,
+    D5: InputPin + OutputPin,
+    D6: InputPin + OutputPin,
+    D7: InputPin + OutputPin,
+> {
+    rs: RS,
+    rw: RW,
+    en: EN,
+    d0: D0,
+    d1: D1,
+    d2: D2,
+    d3: D3,
+    d4: D4,
+    d5: D5,
+    d6: D6,
+    d7: D7,
+}
+
+impl<
+        RS: OutputPin,
+        RW: OutputPin,
+        EN: OutputPin,
+        D0: InputPin + OutputPin,
+        D1: InputPin + OutputPin,
+        D2: InputPin + OutputPin,
+        D3: InputPin + OutputPin,
+        D4: InputPin + OutputPin,
+        D5: InputPin + OutputPin,
+        D6: InputPin + OutputPin,
+        D7: InputPin + OutputPin,
+    > EightBitRwBus<RS, RW, EN, D0, D1, D2, D3, D4, D5, D6, D7>
+{
+    /// Creates a new `EightBitRwBus` instance.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_pins(
+        rs: RS,
+        rw: RW,
+        en: EN,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+    ) -> EightBitRwBus<RS, RW, EN, D0, D1, D2, D3, D4, D5, D6, D7> {
+        EightBitRwBus {
+            rs,
+            rw,
+            en,
+            d0,
+            d1,
+            d2,
+            d3,
+            d4,
+            d5,
+            d6,
+            d7,
+        }
+    }
+
+    fn set_bus_bits(&mut self, data: u8) -> Result<(), ErrorKind> {
+        set_pin(&mut self.d0, data & 0b0000_0001 != 0)?;
+        set_pin(&mut self.d1, data & 0b0000_0010 != 0)?;
+        set_pin(&mut self.d2, data & 0b0000_0100 != 0)?;
+        set_pin(&mut self.d3, data & 0b0000_1000 != 0)?;
+        set_pin(&mut self.d4, data & 0b0001_0000 != 0)?;
+        set_pin(&mut self.d5, data & 0b0010_0000 != 0)?;
+        set_pin(&mut self.d6, data & 0b0100_0000 != 0)?;
+        set_pin(&mut self.d7, data & 0b1000_0000 != 0)?;
+
+        Ok(())
+    }
+
+    fn get_bus_bits(&mut self) -> Result<u8, ErrorKind> {
+        let db0 = is_high(&mut self.d0)? as u8;
+        let db1 = is_high(&mut self.d1)? as u8;
+        let db2 = is_high(&mut self.d2)? as u8;
+        let db3 = is_high(&mut self.d3)? as u8;
+        let db4 = is_high(&mut self.d4)? as u8;
+        let db5 = is_high(&mut self.d5)? as u8;
+        let db6 = is_high(&mut self.d6)? as u8;
+        let db7 = is_high(&mut self.d7)? as u8;
+
+        Ok(db7 << 7 | db6 << 6 | db5 << 5 | db4 << 4 | db3 << 3 | db2 << 2 | db1 << 1 | db0)
+    }
+}
+
+impl<
+        RS: OutputPin,
+        RW: OutputPin,
+        EN: OutputPin,
+        D0: InputPin + OutputPin,
+        D1: InputPin + OutputPin,
+        D2: InputPin + OutputPin,
+        D3: InputPin + OutputPin,
+        D4: InputPin + OutputPin,
+        D5: InputPin + OutputPin,
+        D6: InputPin + OutputPin,
+        D7: InputPin + OutputPin,
+    > DataBus for EightBitRwBus<RS, RW, EN, D0, D1, D2, D3, D4, D5, D6, D7>
+{
+    type Error = ErrorKind;
+
+    fn write<D: DelayNs>(
+        &mut self,
+        byte: u8,
+        data: bool,
+        delay: &mut D,
+    ) -> Result<(), ErrorKind> {
+        if data {
+            self.rs.set_high().map_err(Error::from_pin)?;
+        } else {
+            self.rs.set_low().map_err(Error::from_pin)?;
+        }
+
+        self.rw.set_low().map_err(Error::from_pin)?;
+
+        self.set_bus_bits(byte)?;
+
+        // Pulse the enable pin
+        self.en.set_high().map_err(Error::from_pin)?;
+        delay.delay_us(1);
+        self.en.set_low().map_err(Error::from_pin)?;
+        delay.delay_us(1);
+
+        Ok(())
+    }
+
+    fn read<D: DelayNs>(&mut self, data: bool, delay: &mut D) -> Result<u8, ErrorKind> {
+        if data {
+            self.rs.set_high().map_err(Error::from_pin)?;
+        } else {
+            self.rs.set_low().map_err(Error::from_pin)?;
+        }
+
+        // Release the data lines so the display can drive them
+        self.set_bus_bits(0xFF)?;
+        self.rw.set_high().map_err(Error::from_pin)?;
+
+        self.en.set_high().map_err(Error::from_pin)?;
+        delay.delay_us(1);
+        let byte = self.get_bus_bits();
+        self.en.set_low().map_err(Error::from_pin)?;
+        delay.delay_us(1);
+
+        self.rw.set_low().map_err(Error::from_pin)?;
+
+        byte
+    }
+
+    fn wait_ready<D: DelayNs>(&mut self, delay: &mut D) -> Result<bool, ErrorKind> {
+        let mut waited = 0;
+
+        while self.read(false, delay)? & BUSY_FLAG != 0 {
+            if waited >= BUSY_TIMEOUT_US {
+                return Err(Error::Timeout);
+            }
+
+            delay.delay_us(BUSY_POLL_INTERVAL_US);
+            waited += BUSY_POLL_INTERVAL_US;
+        }
+
+        Ok(true)
+    }
+}
@@ -1,7 +1,7 @@
 #![allow(warnings)]
 
 use embedded_hal::delay::DelayNs;
-use embedded_hal::digital::OutputPin;
+use embedded_hal::digital::{ErrorKind, OutputPin};
 
 use crate::data_bus::DataBus;
 use crate::error::{Error, Result};
@@ -80,12 +80,14 @@ impl<
         D7: OutputPin,
     > DataBus for BusWidth<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>
 {
+    type Error = ErrorKind;
+
     fn write<D: DelayNs>(
         &mut self,
         byte: u8,
         data: bool,
         delay: &mut D,
-    ) -> Result<()> {
+    ) -> Result<(), ErrorKind> {
         match self {
             BusWidth::FourBitBus(bus) => bus.write(byte, data, delay),
             BusWidth::EightBitBus(bus) => bus.write(byte, data, delay),
@@ -132,67 +134,67 @@ impl<RS: OutputPin, EN: OutputPin, D4: OutputPin, D5: OutputPin, D6: OutputPin,
         }
     }
 
-    fn write_lower_nibble(&mut self, data: u8) -> Result<()> {
+    fn write_lower_nibble(&mut self, data: u8) -> Result<(), ErrorKind> {
         let db0: bool = (0b0000_0001 & data) != 0;
         let db1: bool = (0b0000_0010 & data) != 0;
         let db2: bool = (0b0000_0100 & data) != 0;
         let db3: bool = (0b0000_1000 & data) != 0;
 
         if db0 {
-            self.d4.set_high().map_err(|_| Error)?;
+            self.d4.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d4.set_low().map_err(|_| Error)?;
+            self.d4.set_low().map_err(Error::from_pin)?;
         }
 
         if db1 {
-            self.d5.set_high().map_err(|_| Error)?;
+            self.d5.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d5.set_low().map_err(|_| Error)?;
+            self.d5.set_low().map_err(Error::from_pin)?;
         }
 
         if db2 {
-            self.d6.set_high().map_err(|_| Error)?;
+            self.d6.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d6.set_low().map_err(|_| Error)?;
+            self.d6.set_low().map_err(Error::from_pin)?;
         }
 
         if db3 {
-            self.d7.set_high().map_err(|_| Error)?;
+            self.d7.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d7.set_low().map_err(|_| Error)?;
+            self.d7.set_low().map_err(Error::from_pin)?;
         }
 
         Ok(())
     }
 
-    fn write_upper_nibble(&mut self, data: u8) -> Result<()> {
+    fn write_upper_nibble(&mut self, data: u8) -> Result<(), ErrorKind> {
         let db4: bool = (0b0001_0000 & data) != 0;
         let db5: bool = (0b0010_0000 & data) != 0;
         let db6: bool = (0b0100_0000 & data) != 0;
         let db7: bool = (0b1000_0000 & data) != 0;
 
         if db4 {
-            self.d4.set_high().map_err(|_| Error)?;
+            self.d4.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d4.set_low().map_err(|_| Error)?;
+            self.d4.set_low().map_err(Error::from_pin)?;
         }
 
         if db5 {
-            self.d5.set_high().map_err(|_| Error)?;
+            self.d5.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d5.set_low().map_err(|_| Error)?;
+            self.d5.set_low().map_err(Error::from_pin)?;
         }
 
         if db6 {
-            self.d6.set_high().map_err(|_| Error)?;
+            self.d6.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d6.set_low().map_err(|_| Error)?;
+            self.d6.set_low().map_err(Error::from_pin)?;
         }
 
         if db7 {
-            self.d7.set_high().map_err(|_| Error)?;
+            self.d7.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d7.set_low().map_err(|_| Error)?;
+            self.d7.set_low().map_err(Error::from_pin)?;
         }
         Ok(())
     }
@@ -201,34 +203,36 @@ impl<RS: OutputPin, EN: OutputPin, D4: OutputPin, D5: OutputPin, D6: OutputPin,
 impl<RS: OutputPin, EN: OutputPin, D4: OutputPin, D5: OutputPin, D6: OutputPin, D7: OutputPin>
     DataBus for FourBitBus<RS, EN, D4, D5, D6, D7>
 {
+    type Error = ErrorKind;
+
     fn write<D: DelayNs>(
         &mut self,
         byte: u8,
         data: bool,
         delay: &mut D,
-    ) -> Result<()> {
+    ) -> Result<(), ErrorKind> {
         if data {
-            self.rs.set_high().map_err(|_| Error)?;
+            self.rs.set_high().map_err(Error::from_pin)?;
         } else {
-            self.rs.set_low().map_err(|_| Error)?;
+            self.rs.set_low().map_err(Error::from_pin)?;
         }
 
         self.write_upper_nibble(byte)?;
 
         // Pulse the enable pin to receive the upper nibble
-        self.en.set_high().map_err(|_| Error)?;
+        self.en.set_high().map_err(Error::from_pin)?;
         delay.delay_ms(2u32);
-        self.en.set_low().map_err(|_| Error)?;
+        self.en.set_low().map_err(Error::from_pin)?;
 
         self.write_lower_nibble(byte)?;
 
         // Pulse the enable pin to receive the lower nibble
-        self.en.set_high().map_err(|_| Error)?;
+        self.en.set_high().map_err(Error::from_pin)?;
         delay.delay_ms(2u32);
-        self.en.set_low().map_err(|_| Error)?;
+        self.en.set_low().map_err(Error::from_pin)?;
 
         if data {
-            self.rs.set_low().map_err(|_| Error)?;
+            self.rs.set_low().map_err(Error::from_pin)?;
         }
         Ok(())
     }
@@ -313,16 +317,18 @@ impl<
         D7: OutputPin,
     > DataBus for EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>
 {
+    type Error = ErrorKind;
+
     fn write<D: DelayNs>(
         &mut self,
         byte: u8,
         data: bool,
         delay: &mut D,
-    ) -> Result<()> {
+    ) -> Result<(), ErrorKind> {
         if data {
-            self.rs.set_high().map_err(|_| Error)?;
+            self.rs.set_high().map_err(Error::from_pin)?;
         } else {
-            self.rs.set_low().map_err(|_| Error)?;
+            self.rs.set_low().map_err(Error::from_pin)?;
         }
 
         let db0: bool = (0b0000_0001 & byte) != 0;
@@ -335,60 +341,60 @@ impl<
         let db7: bool = (0b1000_0000 & byte) != 0;
 
         if db0 {
-            self.d0.set_high().map_err(|_| Error)?;
+            self.d0.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d0.set_low().map_err(|_| Error)?;
+            self.d0.set_low().map_err(Error::from_pin)?;
         }
 
         if db1 {
-            self.d1.set_high().map_err(|_| Error)?;
+            self.d1.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d1.set_low().map_err(|_| Error)?;
+            self.d1.set_low().map_err(Error::from_pin)?;
         }
 
         if db2 {
-            self.d2.set_high().map_err(|_| Error)?;
+            self.d2.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d2.set_low().map_err(|_| Error)?;
+            self.d2.set_low().map_err(Error::from_pin)?;
         }
 
         if db3 {
-            self.d3.set_high().map_err(|_| Error)?;
+            self.d3.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d3.set_low().map_err(|_| Error)?;
+            self.d3.set_low().map_err(Error::from_pin)?;
         }
 
         if db4 {
-            self.d4.set_high().map_err(|_| Error)?;
+            self.d4.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d4.set_low().map_err(|_| Error)?;
+            self.d4.set_low().map_err(Error::from_pin)?;
         }
 
         if db5 {
-            self.d5.set_high().map_err(|_| Error)?;
+            self.d5.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d5.set_low().map_err(|_| Error)?;
+            self.d5.set_low().map_err(Error::from_pin)?;
         }
 
         if db6 {
-            self.d6.set_high().map_err(|_| Error)?;
+            self.d6.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d6.set_low().map_err(|_| Error)?;
+            self.d6.set_low().map_err(Error::from_pin)?;
         }
 
         if db7 {
-            self.d7.set_high().map_err(|_| Error)?;
+            self.d7.set_high().map_err(Error::from_pin)?;
         } else {
-            self.d7.set_low().map_err(|_| Error)?;
+            self.d7.set_low().map_err(Error::from_pin)?;
         }
 
         // Pulse the enable pin
-        self.en.set_high().map_err(|_| Error)?;
+        self.en.set_high().map_err(Error::from_pin)?;
         delay.delay_ms(2u32);
-        self.en.set_low().map_err(|_| Error)?;
+        self.en.set_low().map_err(Error::from_pin)?;
 
         if data {
-            self.rs.set_low().map_err(|_| Error)?;
+            self.rs.set_low().map_err(Error::from_pin)?;
         }
         Ok(())
     }
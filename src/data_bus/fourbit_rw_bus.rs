@@ -0,0 +1,178 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{ErrorKind, InputPin, OutputPin};
+
+use crate::data_bus::{is_high, set_pin, DataBus, BUSY_FLAG, BUSY_POLL_INTERVAL_US, BUSY_TIMEOUT_US};
+use crate::error::{Error, Result};
+
+/// A struct for 4-bit bus communication with a read/write pin.
+///
+/// The data pins are read back to poll the busy flag, so they need to be able to
+/// act as inputs as well as outputs. Open-drain pins with pull-ups work, as does
+/// any pin type that implements both traits and releases the line when set high.
+pub struct FourBitRwBus<
+    RS: OutputPin,
+    RW: OutputPin,
+    EN: OutputPin,
+    D4: InputPin + OutputPin,
+    D5: InputPin + OutputPin,
+    D6: InputPin + OutputPin,
+    D7: InputPin + OutputPin,
+> {
+    rs: RS,
+    rw: RW,
+    en: EN,
+    d4: D4,
+    d5: D5,
+    d6: D6,
+    d7: D7,
+}
+
+impl<
+        RS: OutputPin,
+        RW: OutputPin,
+        EN: OutputPin,
+        D4: InputPin + OutputPin,
+        D5: InputPin + OutputPin,
+        D6: InputPin + OutputPin,
+        D7: InputPin + OutputPin,
+    > FourBitRwBus<RS, RW, EN, D4, D5, D6, D7>
+{
+    /// Creates a new `FourBitRwBus` instance.
+    pub fn from_pins(
+        rs: RS,
+        rw: RW,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+    ) -> FourBitRwBus<RS, RW, EN, D4, D5, D6, D7> {
+        FourBitRwBus {
+            rs,
+            rw,
+            en,
+            d4,
+            d5,
+            d6,
+            d7,
+        }
+    }
+
+    // Put the lower four bits of `nibble` on d4..d7
+    fn set_nibble(&mut self, nibble: u8) -> Result<(), ErrorKind> {
+        set_pin(&mut self.d4, nibble & 0b0001 != 0)?;
+        set_pin(&mut self.d5, nibble & 0b0010 != 0)?;
+        set_pin(&mut self.d6, nibble & 0b0100 != 0)?;
+        set_pin(&mut self.d7, nibble & 0b1000 != 0)?;
+
+        Ok(())
+    }
+
+    // Read d4..d7 into the lower four bits
+    fn get_nibble(&mut self) -> Result<u8, ErrorKind> {
+        let db4 = is_high(&mut self.d4)? as u8;
+        let db5 = is_high(&mut self.d5)? as u8;
+        let db6 = is_high(&mut self.d6)? as u8;
+        let db7 = is_high(&mut self.d7)? as u8;
+
+        Ok(db7 << 3 | db6 << 2 | db5 << 1 | db4)
+    }
+
+    fn pulse_enable<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), ErrorKind> {
+        self.en.set_high().map_err(Error::from_pin)?;
+        delay.delay_us(1);
+        self.en.set_low().map_err(Error::from_pin)?;
+        delay.delay_us(1);
+
+        Ok(())
+    }
+
+    fn read_nibble<D: DelayNs>(&mut self, delay: &mut D) -> Result<u8, ErrorKind> {
+        self.en.set_high().map_err(Error::from_pin)?;
+        delay.delay_us(1);
+        let nibble = self.get_nibble();
+        self.en.set_low().map_err(Error::from_pin)?;
+        delay.delay_us(1);
+
+        nibble
+    }
+}
+
+impl<
+        RS: OutputPin,
+        RW: OutputPin,
+        EN: OutputPin,
+        D4: InputPin + OutputPin,
+        D5: InputPin + OutputPin,
+        D6: InputPin + OutputPin,
+        D7: InputPin + OutputPin,
+    > DataBus for FourBitRwBus<RS, RW, EN, D4, D5, D6, D7>
+{
+    type Error = ErrorKind;
+
+    fn write<D: DelayNs>(
+        &mut self,
+        byte: u8,
+        data: bool,
+        delay: &mut D,
+    ) -> Result<(), ErrorKind> {
+        if data {
+            self.rs.set_high().map_err(Error::from_pin)?;
+        } else {
+            self.rs.set_low().map_err(Error::from_pin)?;
+        }
+
+        self.rw.set_low().map_err(Error::from_pin)?;
+
+        self.set_nibble(byte >> 4)?;
+        self.pulse_enable(delay)?;
+
+        self.set_nibble(byte & 0x0F)?;
+        self.pulse_enable(delay)?;
+
+        Ok(())
+    }
+
+    fn write_nibble<D: DelayNs>(&mut self, nibble: u8, delay: &mut D) -> Result<(), ErrorKind> {
+        self.rs.set_low().map_err(Error::from_pin)?;
+        self.rw.set_low().map_err(Error::from_pin)?;
+
+        self.set_nibble(nibble >> 4)?;
+        self.pulse_enable(delay)
+    }
+
+    // Read the byte as two nibbles, upper nibble first
+    fn read<D: DelayNs>(&mut self, data: bool, delay: &mut D) -> Result<u8, ErrorKind> {
+        if data {
+            self.rs.set_high().map_err(Error::from_pin)?;
+        } else {
+            self.rs.set_low().map_err(Error::from_pin)?;
+        }
+
+        // Release the data lines so the display can drive them
+        self.set_nibble(0b1111)?;
+        self.rw.set_high().map_err(Error::from_pin)?;
+
+        let upper = self.read_nibble(delay);
+        let lower = self.read_nibble(delay);
+
+        self.rw.set_low().map_err(Error::from_pin)?;
+
+        Ok((upper? << 4) | lower?)
+    }
+
+    fn wait_ready<D: DelayNs>(&mut self, delay: &mut D) -> Result<bool, ErrorKind> {
+        let mut waited = 0;
+
+        while self.read(false, delay)? & BUSY_FLAG != 0 {
+            if waited >= BUSY_TIMEOUT_US {
+                return Err(Error::Timeout);
+            }
+
+            delay.delay_us(BUSY_POLL_INTERVAL_US);
+            waited += BUSY_POLL_INTERVAL_US;
+        }
+
+        Ok(true)
+    }
+}
@@ -1,57 +1,142 @@
 use embedded_hal::delay::DelayNs;
 use embedded_hal::i2c::I2c;
-use crate::{data_bus::DataBus, error::Result};
+use crate::{
+    data_bus::{Backlight, DataBus, PinMap},
+    error::{Error, Result},
+};
 
 /// A struct for I2C bus communication.
 pub struct I2CBus<I2C: I2c> {
     i2c_bus: I2C,
     address: u8,
+    pin_map: PinMap,
+    backlight: bool,
 }
 
-const BACKLIGHT: u8 = 0b0000_1000;
-const ENABLE: u8 = 0b0000_0100;
-// const READ_WRITE: u8 = 0b0000_0010; // Not used as no reading of the `LCD1602` is done
-const REGISTER_SELECT: u8 = 0b0000_0001;
-
 impl<I2C: I2c> I2CBus<I2C> {
-    /// Creates a new `I2CBus` instance.
+    /// Creates a new `I2CBus` instance for a backpack using the
+    /// [generic](PinMap::GENERIC) pin mapping.
     pub fn new(i2c_bus: I2C, address: u8) -> I2CBus<I2C> {
-        I2CBus { i2c_bus, address }
+        I2CBus {
+            i2c_bus,
+            address,
+            pin_map: PinMap::default(),
+            backlight: true,
+        }
+    }
+
+    /// Creates a new `I2CBus` instance for a backpack wired as described by `pin_map`.
+    ///
+    /// Returns an error if the mapping uses a pin past P7.
+    pub fn with_pin_map(
+        i2c_bus: I2C,
+        address: u8,
+        pin_map: PinMap,
+    ) -> Result<I2CBus<I2C>, I2C::Error> {
+        pin_map.check(8)?;
+
+        Ok(I2CBus {
+            i2c_bus,
+            address,
+            pin_map,
+            backlight: true,
+        })
+    }
+
+    fn port_byte(&self, nibble: u8, rs: bool, rw: bool, en: bool) -> u8 {
+        self.pin_map.port_bits(nibble, rs, rw, en, self.backlight) as u8
     }
 
     /// Write a nibble to the lcd
     /// The nibble should be in the upper part of the byte
-    fn write_nibble<D: DelayNs>(
+    fn send_nibble<D: DelayNs>(
         &mut self,
         nibble: u8,
         data: bool,
         delay: &mut D,
-    ) {
-        let rs = match data {
-            false => 0u8,
-            true => REGISTER_SELECT,
-        };
-        let byte = nibble | rs | BACKLIGHT;
-
-        let _ = self.i2c_bus.write(self.address, &[byte, byte | ENABLE]);
+    ) -> Result<(), I2C::Error> {
+        let byte = self.port_byte(nibble >> 4, data, false, false);
+        let enable = self.port_byte(nibble >> 4, data, false, true);
+
+        self.i2c_bus
+            .write(self.address, &[byte, enable])
+            .map_err(Error::from_i2c)?;
         delay.delay_ms(2u32);
-        let _ = self.i2c_bus.write(self.address, &[byte]);
+        self.i2c_bus
+            .write(self.address, &[byte])
+            .map_err(Error::from_i2c)
+    }
+
+    /// Read a nibble from the lcd
+    /// The nibble is returned in the upper part of the byte
+    fn read_nibble<D: DelayNs>(
+        &mut self,
+        data: bool,
+        delay: &mut D,
+    ) -> Result<u8, I2C::Error> {
+        if self.pin_map.rw.is_none() {
+            return Err(Error::Unsupported);
+        }
+
+        // The data pins are driven high so the lcd can pull them low
+        let byte = self.port_byte(0x0F, data, true, false);
+        let enable = self.port_byte(0x0F, data, true, true);
+        let mut buffer = [0u8];
+
+        self.i2c_bus
+            .write(self.address, &[byte, enable])
+            .map_err(Error::from_i2c)?;
+        delay.delay_us(1);
+        self.i2c_bus
+            .read(self.address, &mut buffer)
+            .map_err(Error::from_i2c)?;
+        self.i2c_bus
+            .write(self.address, &[byte])
+            .map_err(Error::from_i2c)?;
+
+        Ok(self.pin_map.nibble(buffer[0].into()) << 4)
     }
 }
 
 impl<I2C: I2c> DataBus for I2CBus<I2C> {
+    type Error = I2C::Error;
+
     fn write<D: DelayNs>(
         &mut self,
         byte: u8,
         data: bool,
         delay: &mut D,
-    ) -> Result<()> {
+    ) -> Result<(), I2C::Error> {
         let upper_nibble = byte & 0xF0;
-        self.write_nibble(upper_nibble, data, delay);
+        self.send_nibble(upper_nibble, data, delay)?;
 
         let lower_nibble = (byte & 0x0F) << 4;
-        self.write_nibble(lower_nibble, data, delay);
+        self.send_nibble(lower_nibble, data, delay)
+    }
+
+    fn write_nibble<D: DelayNs>(&mut self, nibble: u8, delay: &mut D) -> Result<(), I2C::Error> {
+        self.send_nibble(nibble & 0xF0, false, delay)
+    }
+
+    fn read<D: DelayNs>(&mut self, data: bool, delay: &mut D) -> Result<u8, I2C::Error> {
+        let upper_nibble = self.read_nibble(data, delay)?;
+        let lower_nibble = self.read_nibble(data, delay)?;
+
+        Ok(upper_nibble | (lower_nibble >> 4))
+    }
+}
+
+impl<I2C: I2c> Backlight for I2CBus<I2C> {
+    fn set_backlight(&mut self, on: bool) -> Result<(), I2C::Error> {
+        self.backlight = on;
+
+        let byte = self.port_byte(0, false, false, false);
+        self.i2c_bus
+            .write(self.address, &[byte])
+            .map_err(Error::from_i2c)
+    }
 
-        Ok(())
+    fn backlight(&self) -> bool {
+        self.backlight
     }
 }
@@ -0,0 +1,198 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+use crate::{
+    data_bus::{Backlight, DataBus, PinMap},
+    error::{Error, Result},
+};
+
+/// The MCP230xx port expanders supported by [Mcp230xxBus].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mcp230xx {
+    /// The 8 pin MCP23008. Pins 0..=7 are GP0..GP7.
+    Mcp23008,
+    /// The 16 pin MCP23017. Pins 0..=7 are GPA0..GPA7 and pins 8..=15 are GPB0..GPB7.
+    Mcp23017,
+}
+
+// Register addresses, for the MCP23017 these are the port A registers with IOCON.BANK = 0
+const IODIR: [u8; 2] = [0x00, 0x00];
+const IOCON: [u8; 2] = [0x05, 0x0A];
+const GPIO: [u8; 2] = [0x09, 0x12];
+const OLAT: [u8; 2] = [0x0A, 0x14];
+
+// Turn off the address pointer increment so repeated writes hit the same register,
+// or with an MCP23017 alternate between the A and B register of a pair
+const IOCON_SEQOP: u16 = 0b0010_0000_0010_0000;
+
+/// A struct for communication through an MCP23008 or MCP23017 I2C port expander,
+/// as used on the Adafruit I2C/SPI backpack and the Adafruit RGB LCD shield.
+///
+/// The expander takes I2C clocks up to 1.7 MHz and each enable pulse is sent as
+/// part of a single I2C transfer, so no sleeping is done between nibbles.
+pub struct Mcp230xxBus<I2C: I2c> {
+    i2c_bus: I2C,
+    address: u8,
+    variant: Mcp230xx,
+    pin_map: PinMap,
+    backlight: bool,
+}
+
+impl<I2C: I2c> Mcp230xxBus<I2C> {
+    /// Creates a new `Mcp230xxBus` instance.
+    /// `address` is the full 7 bit I2C address, 0x20..=0x27 depending on the address pins.
+    ///
+    /// Returns an error if the mapping uses a pin the expander does not have.
+    pub fn new(
+        i2c_bus: I2C,
+        address: u8,
+        variant: Mcp230xx,
+        pin_map: PinMap,
+    ) -> Result<Mcp230xxBus<I2C>, I2C::Error> {
+        let pins = match variant {
+            Mcp230xx::Mcp23008 => 8,
+            Mcp230xx::Mcp23017 => 16,
+        };
+        pin_map.check(pins)?;
+
+        Ok(Mcp230xxBus {
+            i2c_bus,
+            address,
+            variant,
+            pin_map,
+            backlight: true,
+        })
+    }
+
+    /// Sets up the expander registers: sequential addressing off, the pins wired
+    /// to the display as outputs and every other pin left as an input.
+    pub(crate) fn configure(&mut self) -> Result<(), I2C::Error> {
+        self.write_register(IOCON, IOCON_SEQOP)?;
+
+        let outputs = self.pin_map.output_bits();
+        self.write_register(IODIR, !outputs)?;
+
+        let idle = self.port_bits(0, false, false, false);
+        self.write_register(OLAT, idle)
+    }
+
+    fn port_bits(&self, nibble: u8, rs: bool, rw: bool, en: bool) -> u16 {
+        self.pin_map.port_bits(nibble, rs, rw, en, self.backlight)
+    }
+
+    fn register(&self, register: [u8; 2]) -> u8 {
+        match self.variant {
+            Mcp230xx::Mcp23008 => register[0],
+            Mcp230xx::Mcp23017 => register[1],
+        }
+    }
+
+    fn write_register(&mut self, register: [u8; 2], value: u16) -> Result<(), I2C::Error> {
+        self.write_port(register, &[value])
+    }
+
+    // Write each value to a register in turn within a single transfer
+    fn write_port(&mut self, register: [u8; 2], values: &[u16]) -> Result<(), I2C::Error> {
+        let mut buffer = [0u8; 7];
+        buffer[0] = self.register(register);
+
+        let mut length = 1;
+        for &value in values {
+            let [a, b] = value.to_le_bytes();
+            buffer[length] = a;
+            length += 1;
+
+            if self.variant == Mcp230xx::Mcp23017 {
+                buffer[length] = b;
+                length += 1;
+            }
+        }
+
+        self.i2c_bus
+            .write(self.address, &buffer[..length])
+            .map_err(Error::from_i2c)
+    }
+
+    fn read_port(&mut self, register: [u8; 2]) -> Result<u16, I2C::Error> {
+        let mut buffer = [0u8; 2];
+        let length = match self.variant {
+            Mcp230xx::Mcp23008 => 1,
+            Mcp230xx::Mcp23017 => 2,
+        };
+
+        self.i2c_bus
+            .write_read(self.address, &[self.register(register)], &mut buffer[..length])
+            .map_err(Error::from_i2c)?;
+
+        Ok(u16::from_le_bytes(buffer))
+    }
+
+    /// Write a nibble to the lcd
+    /// The nibble should be in the lower part of the byte
+    fn send_nibble(&mut self, nibble: u8, data: bool) -> Result<(), I2C::Error> {
+        let bits = self.port_bits(nibble, data, false, false);
+        let enable = self.port_bits(nibble, data, false, true);
+
+        self.write_port(OLAT, &[bits, enable, bits])
+    }
+
+    /// Read a nibble from the lcd
+    /// The nibble is returned in the lower part of the byte
+    fn read_nibble(&mut self, data: bool) -> Result<u8, I2C::Error> {
+        let bits = self.port_bits(0, data, true, false);
+        let enable = self.port_bits(0, data, true, true);
+
+        self.write_port(OLAT, &[bits, enable])?;
+        let port = self.read_port(GPIO);
+        self.write_port(OLAT, &[bits])?;
+
+        Ok(self.pin_map.nibble(port?))
+    }
+}
+
+impl<I2C: I2c> DataBus for Mcp230xxBus<I2C> {
+    type Error = I2C::Error;
+
+    fn write<D: DelayNs>(
+        &mut self,
+        byte: u8,
+        data: bool,
+        _delay: &mut D,
+    ) -> Result<(), I2C::Error> {
+        self.send_nibble(byte >> 4, data)?;
+        self.send_nibble(byte & 0x0F, data)
+    }
+
+    fn write_nibble<D: DelayNs>(&mut self, nibble: u8, _delay: &mut D) -> Result<(), I2C::Error> {
+        self.send_nibble(nibble >> 4, false)
+    }
+
+    fn read<D: DelayNs>(&mut self, data: bool, _delay: &mut D) -> Result<u8, I2C::Error> {
+        if self.pin_map.rw.is_none() {
+            return Err(Error::Unsupported);
+        }
+
+        // Turn the data pins around so the lcd can drive them
+        let outputs = self.pin_map.output_bits();
+        self.write_register(IODIR, !outputs | self.pin_map.data_bits())?;
+
+        let upper_nibble = self.read_nibble(data);
+        let lower_nibble = self.read_nibble(data);
+
+        self.write_register(IODIR, !outputs)?;
+
+        Ok(upper_nibble? << 4 | lower_nibble?)
+    }
+}
+
+impl<I2C: I2c> Backlight for Mcp230xxBus<I2C> {
+    fn set_backlight(&mut self, on: bool) -> Result<(), I2C::Error> {
+        self.backlight = on;
+
+        let bits = self.port_bits(0, false, false, false);
+        self.write_register(OLAT, bits)
+    }
+
+    fn backlight(&self) -> bool {
+        self.backlight
+    }
+}
@@ -1,17 +1,56 @@
 mod eightbit_bus;
+mod eightbit_rw_bus;
 mod fourbit_bus;
+mod fourbit_rw_bus;
 mod i2c_bus;
+mod mcp230xx_bus;
+mod pin_map;
+mod shift_register_bus;
 mod fourbit_eightbit_bus;
+mod dual_bus;
+#[cfg(feature = "async")]
+mod async_i2c_bus;
 
 use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{ErrorKind, InputPin, OutputPin};
 pub use self::eightbit_bus::EightBitBus;
+pub use self::eightbit_rw_bus::EightBitRwBus;
 pub use self::fourbit_bus::FourBitBus;
+pub use self::fourbit_rw_bus::FourBitRwBus;
 pub use self::i2c_bus::I2CBus;
+pub use self::mcp230xx_bus::{Mcp230xx, Mcp230xxBus};
+pub use self::pin_map::{BacklightPolarity, PinMap};
+pub use self::shift_register_bus::{GpioShiftRegister, ShiftOut, ShiftRegisterBus, SpiShiftRegister};
+pub use self::dual_bus::{EightBitDualBus, FourBitDualBus};
+#[cfg(feature = "async")]
+pub use self::async_i2c_bus::AsyncI2CBus;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
+
+// Bit 7 of the instruction register read is set while an instruction is executing
+const BUSY_FLAG: u8 = 0b1000_0000;
+// How often and for how long to poll the busy flag before giving up.
+// The slowest instructions take 1.52 ms at the nominal oscillator frequency.
+const BUSY_POLL_INTERVAL_US: u32 = 10;
+const BUSY_TIMEOUT_US: u32 = 10_000;
+
+/// The controllers of a display that has two, such as 40x4 panels, which share
+/// every line but the enable line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Controller {
+    /// The controller of the upper two rows.
+    First,
+    /// The controller of the lower two rows.
+    Second,
+    /// Both controllers at once.
+    Both,
+}
 
 /// A trait for LCD display buses.
 pub trait DataBus {
+    /// The error type of the underlying bus.
+    type Error: core::fmt::Debug;
+
     /// Sends a command to the display.
     /// `byte`: The command to send.
     /// `delay`: A delay provider.
@@ -22,5 +61,107 @@ pub trait DataBus {
         byte: u8,
         data: bool,
         delay: &mut D,
-    ) -> Result<()>;
+    ) -> Result<(), Self::Error>;
+
+    /// Sends the upper four bits of a command as a single transfer. This is only used
+    /// while initializing 4-bit buses, before the display has been switched to 4-bit mode.
+    /// Buses that always transfer whole bytes send the full byte.
+    /// `nibble`: The command, with the nibble to send in its upper four bits.
+    /// `delay`: A delay provider.
+    fn write_nibble<D: DelayNs>(&mut self, nibble: u8, delay: &mut D) -> Result<(), Self::Error> {
+        self.write(nibble, false, delay)
+    }
+
+    /// Reads a byte from the display.
+    /// `data`: Whether to read data from DDRAM/CGRAM, or the busy flag and address counter.
+    /// `delay`: A delay provider.
+    /// Returns: The byte read, or `Err(Error::Unsupported)` if the bus cannot read from the display.
+    fn read<D: DelayNs>(&mut self, data: bool, delay: &mut D) -> Result<u8, Self::Error> {
+        let _ = (data, delay);
+        Err(Error::Unsupported)
+    }
+
+    /// Waits until the display has finished the last instruction by polling its busy flag.
+    /// `delay`: A delay provider.
+    /// Returns: `Ok(true)` once the display is ready, `Ok(false)` if the bus cannot read
+    /// the busy flag and the caller has to wait a fixed time instead, or `Err(Error::Timeout)`
+    /// if the display stayed busy for too long.
+    fn wait_ready<D: DelayNs>(&mut self, delay: &mut D) -> Result<bool, Self::Error> {
+        let _ = delay;
+        Ok(false)
+    }
+
+    /// Selects the controller later transfers go to, on buses with an enable line
+    /// for each of two controllers. Buses for a single controller ignore this.
+    /// `controller`: The controller to pulse the enable line of.
+    fn select_controller(&mut self, controller: Controller) -> Result<(), Self::Error> {
+        let _ = controller;
+        Ok(())
+    }
+}
+
+/// A trait for buses that can switch the display backlight.
+pub trait Backlight: DataBus {
+    /// Turns the backlight on or off. The new state is written straight away and
+    /// kept for every later write.
+    fn set_backlight(&mut self, on: bool) -> Result<(), Self::Error>;
+
+    /// Returns whether the backlight is on.
+    fn backlight(&self) -> bool;
+}
+
+/// The async counterpart of [DataBus], for buses built on `embedded-hal-async`.
+#[cfg(feature = "async")]
+#[allow(async_fn_in_trait)]
+pub trait AsyncDataBus {
+    /// The error type of the underlying bus.
+    type Error: core::fmt::Debug;
+
+    /// Sends a command to the display.
+    /// `byte`: The command to send.
+    /// `delay`: A delay provider.
+    /// `data`: Whether the command is data or a command.
+    /// Returns: `Ok(())` if the command was sent successfully, `Err(Error)` otherwise.
+    async fn write<D: embedded_hal_async::delay::DelayNs>(
+        &mut self,
+        byte: u8,
+        data: bool,
+        delay: &mut D,
+    ) -> Result<(), Self::Error>;
+
+    /// Sends the upper four bits of a command as a single transfer, see
+    /// [DataBus::write_nibble].
+    /// `nibble`: The command, with the nibble to send in its upper four bits.
+    /// `delay`: A delay provider.
+    async fn write_nibble<D: embedded_hal_async::delay::DelayNs>(
+        &mut self,
+        nibble: u8,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.write(nibble, false, delay).await
+    }
+}
+
+/// The async counterpart of [Backlight].
+#[cfg(feature = "async")]
+#[allow(async_fn_in_trait)]
+pub trait AsyncBacklight: AsyncDataBus {
+    /// Turns the backlight on or off. The new state is written straight away and
+    /// kept for every later write.
+    async fn set_backlight(&mut self, on: bool) -> Result<(), Self::Error>;
+
+    /// Returns whether the backlight is on.
+    fn backlight(&self) -> bool;
+}
+
+fn set_pin<P: OutputPin>(pin: &mut P, high: bool) -> Result<(), ErrorKind> {
+    if high {
+        pin.set_high().map_err(Error::from_pin)
+    } else {
+        pin.set_low().map_err(Error::from_pin)
+    }
+}
+
+fn is_high<P: InputPin>(pin: &mut P) -> Result<bool, ErrorKind> {
+    pin.is_high().map_err(Error::from_pin)
 }
@@ -0,0 +1,241 @@
+use crate::error::{Error, Result};
+
+/// Whether the backlight is lit by driving its pin high or low.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BacklightPolarity {
+    /// The backlight is on while its pin is high.
+    ActiveHigh,
+    /// The backlight is on while its pin is low.
+    ActiveLow,
+}
+
+/// Which port expander pin each display line is wired to.
+///
+/// Pins are numbered from 0 (P0 on a PCF8574, GP0 or GPA0 on an MCP230xx,
+/// QA on a 74HC595).
+/// Pins 8 and up are only available on the 16 pin MCP23017. Buses return
+/// [Error::InvalidPinMap] when they are built with a mapping that uses a pin they
+/// don't have. Use one of the presets for common backpack boards, or build a
+/// custom mapping:
+///
+/// ```rust,ignore
+/// let pin_map = PinMap {
+///     backlight_polarity: BacklightPolarity::ActiveLow,
+///     ..PinMap::GENERIC
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PinMap {
+    /// Register select pin.
+    pub rs: u8,
+    /// Read/write pin, `None` if the line is tied to ground.
+    pub rw: Option<u8>,
+    /// Enable pin.
+    pub en: u8,
+    /// The pins wired to d4, d5, d6 and d7, in that order.
+    pub data: [u8; 4],
+    /// The pins switching the backlight, all switched together.
+    pub backlight: &'static [u8],
+    /// Whether the backlight pins are active high or active low.
+    pub backlight_polarity: BacklightPolarity,
+}
+
+impl PinMap {
+    /// RS=P0, RW=P1, EN=P2, backlight=P3 and d4..d7 on P4..P7.
+    ///
+    /// The wiring of most PCF8574 backpacks, including the YwRobot,
+    /// DFRobot and SainSmart boards.
+    pub const GENERIC: PinMap = PinMap {
+        rs: 0,
+        rw: Some(1),
+        en: 2,
+        data: [4, 5, 6, 7],
+        backlight: &[3],
+        backlight_polarity: BacklightPolarity::ActiveHigh,
+    };
+
+    /// d4..d7 on P0..P3, EN=P4, RW=P5, RS=P6 and an active low backlight on P7.
+    ///
+    /// Used by the mjkdz boards and several LCM1602 clones.
+    pub const MJKDZ: PinMap = PinMap {
+        rs: 6,
+        rw: Some(5),
+        en: 4,
+        data: [0, 1, 2, 3],
+        backlight: &[7],
+        backlight_polarity: BacklightPolarity::ActiveLow,
+    };
+
+    /// RS=1, EN=2, d4..d7 on 3..6 and backlight=7, with RW tied to ground.
+    ///
+    /// The Adafruit style wiring, as on the MCP23008 of the Adafruit I2C/SPI backpack.
+    pub const ADAFRUIT: PinMap = PinMap {
+        rs: 1,
+        rw: None,
+        en: 2,
+        data: [3, 4, 5, 6],
+        backlight: &[7],
+        backlight_polarity: BacklightPolarity::ActiveHigh,
+    };
+
+    /// RS=1, EN=2, d4..d7 on 6..3 and backlight=7, with RW tied to ground.
+    ///
+    /// The wiring of the 74HC595 on the Adafruit I2C/SPI backpack in SPI mode,
+    /// where the data lines come off the shift register in reverse order.
+    pub const ADAFRUIT_SPI: PinMap = PinMap {
+        rs: 1,
+        rw: None,
+        en: 2,
+        data: [6, 5, 4, 3],
+        backlight: &[7],
+        backlight_polarity: BacklightPolarity::ActiveHigh,
+    };
+
+    /// RS=GPB7, RW=GPB6, EN=GPB5, d4..d7 on GPB4..GPB1 and an active low RGB
+    /// backlight on GPA6, GPA7 and GPB0, switched together.
+    ///
+    /// The wiring of the Adafruit RGB LCD shield, which uses an MCP23017.
+    pub const ADAFRUIT_RGB_SHIELD: PinMap = PinMap {
+        rs: 15,
+        rw: Some(14),
+        en: 13,
+        data: [12, 11, 10, 9],
+        backlight: &[6, 7, 8],
+        backlight_polarity: BacklightPolarity::ActiveLow,
+    };
+
+    // Check that every pin is one of the `pins` pins of the expander
+    pub(crate) fn check<E>(&self, pins: u8) -> Result<(), E> {
+        let mut used = self
+            .data
+            .iter()
+            .chain([&self.rs, &self.en])
+            .chain(&self.rw)
+            .chain(self.backlight);
+
+        if used.all(|&pin| pin < pins) {
+            Ok(())
+        } else {
+            Err(Error::InvalidPinMap)
+        }
+    }
+
+    // Build the port value for a nibble (in the lower four bits) and control lines
+    pub(crate) fn port_bits(
+        &self,
+        nibble: u8,
+        rs: bool,
+        rw: bool,
+        en: bool,
+        backlight: bool,
+    ) -> u16 {
+        let mut bits = 0u16;
+
+        for (bit, &pin) in self.data.iter().enumerate() {
+            if nibble & (1 << bit) != 0 {
+                bits |= 1 << pin;
+            }
+        }
+
+        if rs {
+            bits |= 1 << self.rs;
+        }
+
+        if let (true, Some(pin)) = (rw, self.rw) {
+            bits |= 1 << pin;
+        }
+
+        if en {
+            bits |= 1 << self.en;
+        }
+
+        if backlight == (self.backlight_polarity == BacklightPolarity::ActiveHigh) {
+            bits |= self.backlight_bits();
+        }
+
+        bits
+    }
+
+    // Pick the nibble back out of a port value read from the expander
+    pub(crate) fn nibble(&self, bits: u16) -> u8 {
+        let mut nibble = 0;
+
+        for (bit, &pin) in self.data.iter().enumerate() {
+            if bits & (1 << pin) != 0 {
+                nibble |= 1 << bit;
+            }
+        }
+
+        nibble
+    }
+
+    pub(crate) fn data_bits(&self) -> u16 {
+        self.data.iter().fold(0, |bits, &pin| bits | 1 << pin)
+    }
+
+    // Every pin the display is wired to, which all need to be outputs
+    pub(crate) fn output_bits(&self) -> u16 {
+        let rw = self.rw.map_or(0, |pin| 1 << pin);
+
+        self.data_bits() | 1 << self.rs | rw | 1 << self.en | self.backlight_bits()
+    }
+
+    pub(crate) fn backlight_bits(&self) -> u16 {
+        self.backlight.iter().fold(0, |bits, &pin| bits | 1 << pin)
+    }
+}
+
+impl Default for PinMap {
+    /// The [GENERIC](#associatedconstant.GENERIC) wiring.
+    fn default() -> Self {
+        PinMap::GENERIC
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_fit_their_expander() {
+        for pin_map in [
+            PinMap::GENERIC,
+            PinMap::MJKDZ,
+            PinMap::ADAFRUIT,
+            PinMap::ADAFRUIT_SPI,
+        ] {
+            assert_eq!(pin_map.check::<()>(8), Ok(()));
+        }
+
+        assert_eq!(
+            PinMap::ADAFRUIT_RGB_SHIELD.check::<()>(8),
+            Err(Error::InvalidPinMap)
+        );
+        assert_eq!(PinMap::ADAFRUIT_RGB_SHIELD.check::<()>(16), Ok(()));
+    }
+
+    #[test]
+    fn check_rejects_missing_pins() {
+        let pin_map = PinMap {
+            backlight: &[16],
+            ..PinMap::GENERIC
+        };
+        assert_eq!(pin_map.check::<()>(16), Err(Error::InvalidPinMap));
+
+        let pin_map = PinMap {
+            rw: Some(8),
+            ..PinMap::GENERIC
+        };
+        assert_eq!(pin_map.check::<()>(8), Err(Error::InvalidPinMap));
+    }
+
+    #[test]
+    fn port_bits_follow_the_map() {
+        let bits = PinMap::GENERIC.port_bits(0b1010, true, false, true, true);
+        assert_eq!(bits, 0b1010_1101);
+        assert_eq!(PinMap::GENERIC.nibble(bits), 0b1010);
+
+        let bits = PinMap::MJKDZ.port_bits(0b0001, false, false, false, true);
+        assert_eq!(bits, 0b0000_0001);
+    }
+}
@@ -0,0 +1,142 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{ErrorKind, OutputPin};
+use embedded_hal::spi::SpiDevice;
+use crate::{
+    data_bus::{set_pin, Backlight, DataBus, PinMap},
+    error::{Error, Result},
+};
+
+/// A trait for loading a byte into a 74HC595 shift register and latching it
+/// onto its outputs. Bit 7 ends up on QH and bit 0 on QA.
+pub trait ShiftOut {
+    /// The error type of the underlying peripheral.
+    type Error: core::fmt::Debug;
+
+    /// Shifts out a byte, most significant bit first, and latches it.
+    fn shift_out(&mut self, byte: u8) -> Result<(), Self::Error>;
+}
+
+/// A 74HC595 driven by an SPI peripheral, with the latch pin as chip select.
+/// The outputs are latched when chip select is released after each byte.
+pub struct SpiShiftRegister<SPI: SpiDevice> {
+    spi: SPI,
+}
+
+impl<SPI: SpiDevice> SpiShiftRegister<SPI> {
+    /// Creates a new `SpiShiftRegister` instance.
+    pub fn new(spi: SPI) -> SpiShiftRegister<SPI> {
+        SpiShiftRegister { spi }
+    }
+}
+
+impl<SPI: SpiDevice> ShiftOut for SpiShiftRegister<SPI> {
+    type Error = SPI::Error;
+
+    fn shift_out(&mut self, byte: u8) -> Result<(), SPI::Error> {
+        self.spi.write(&[byte]).map_err(Error::Spi)
+    }
+}
+
+/// A 74HC595 bit-banged over three GPIO pins, for when no SPI peripheral is free.
+pub struct GpioShiftRegister<DATA: OutputPin, CLK: OutputPin, LATCH: OutputPin> {
+    data: DATA,
+    clock: CLK,
+    latch: LATCH,
+}
+
+impl<DATA: OutputPin, CLK: OutputPin, LATCH: OutputPin> GpioShiftRegister<DATA, CLK, LATCH> {
+    /// Creates a new `GpioShiftRegister` instance from the serial data (SER),
+    /// shift clock (SRCLK) and latch (RCLK) pins.
+    pub fn new(data: DATA, clock: CLK, latch: LATCH) -> GpioShiftRegister<DATA, CLK, LATCH> {
+        GpioShiftRegister { data, clock, latch }
+    }
+}
+
+impl<DATA: OutputPin, CLK: OutputPin, LATCH: OutputPin> ShiftOut
+    for GpioShiftRegister<DATA, CLK, LATCH>
+{
+    type Error = ErrorKind;
+
+    fn shift_out(&mut self, byte: u8) -> Result<(), ErrorKind> {
+        for bit in (0..8).rev() {
+            set_pin(&mut self.data, byte & (1 << bit) != 0)?;
+            self.clock.set_high().map_err(Error::from_pin)?;
+            self.clock.set_low().map_err(Error::from_pin)?;
+        }
+
+        self.latch.set_high().map_err(Error::from_pin)?;
+        self.latch.set_low().map_err(Error::from_pin)
+    }
+}
+
+/// A struct for communication through a 74HC595 shift register, as used on the
+/// Adafruit I2C/SPI backpack in SPI mode.
+///
+/// Each shifted out byte is a new state of the display lines, so no sleeping
+/// is done between nibbles.
+pub struct ShiftRegisterBus<S: ShiftOut> {
+    register: S,
+    pin_map: PinMap,
+    backlight: bool,
+}
+
+impl<S: ShiftOut> ShiftRegisterBus<S> {
+    /// Creates a new `ShiftRegisterBus` instance for a shift register wired as
+    /// described by `pin_map`, with the outputs QA..QH as pins 0..7.
+    ///
+    /// Returns an error if the mapping uses a pin past 7.
+    pub fn new(register: S, pin_map: PinMap) -> Result<ShiftRegisterBus<S>, S::Error> {
+        pin_map.check(8)?;
+
+        Ok(ShiftRegisterBus {
+            register,
+            pin_map,
+            backlight: true,
+        })
+    }
+
+    fn port_byte(&self, nibble: u8, rs: bool, en: bool) -> u8 {
+        self.pin_map.port_bits(nibble, rs, false, en, self.backlight) as u8
+    }
+
+    /// Write a nibble to the lcd
+    /// The nibble should be in the lower part of the byte
+    fn send_nibble(&mut self, nibble: u8, data: bool) -> Result<(), S::Error> {
+        let byte = self.port_byte(nibble, data, false);
+
+        self.register.shift_out(byte)?;
+        self.register.shift_out(self.port_byte(nibble, data, true))?;
+        self.register.shift_out(byte)
+    }
+}
+
+impl<S: ShiftOut> DataBus for ShiftRegisterBus<S> {
+    type Error = S::Error;
+
+    fn write<D: DelayNs>(
+        &mut self,
+        byte: u8,
+        data: bool,
+        _delay: &mut D,
+    ) -> Result<(), S::Error> {
+        self.send_nibble(byte >> 4, data)?;
+        self.send_nibble(byte & 0x0F, data)
+    }
+
+    fn write_nibble<D: DelayNs>(&mut self, nibble: u8, _delay: &mut D) -> Result<(), S::Error> {
+        self.send_nibble(nibble >> 4, false)
+    }
+}
+
+impl<S: ShiftOut> Backlight for ShiftRegisterBus<S> {
+    fn set_backlight(&mut self, on: bool) -> Result<(), S::Error> {
+        self.backlight = on;
+
+        let byte = self.port_byte(0, false, false);
+        self.register.shift_out(byte)
+    }
+
+    fn backlight(&self) -> bool {
+        self.backlight
+    }
+}
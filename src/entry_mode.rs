@@ -59,7 +59,6 @@ impl EntryMode {
     //     self.move_direction = direction;
     //     self
     // }
-
     /// Sets the display shift, which will be performed on character write, either `On` or `Off`.
     ///
     /// If display shift is enabled, it will seem as if the cursor does not move but the display
@@ -70,7 +69,6 @@ impl EntryMode {
     //     self.display_shift = shift;
     //     self
     // }
-
     pub fn as_byte(&self) -> u8 {
         let mut cmd = EntryModeFlags::ENTRY_MODE;
 
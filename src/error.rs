@@ -1,6 +1,124 @@
-/// A struct for error handling.
-#[derive(Debug)]
-pub struct Error;
+use core::fmt;
+
+use embedded_hal::digital;
+use embedded_hal::i2c::{self, NoAcknowledgeSource};
+
+/// Errors returned by the driver.
+///
+/// `E` is the error type of the bus the display is connected through. GPIO
+/// buses report the [`ErrorKind`](embedded_hal::digital::ErrorKind) of the
+/// pin that failed, since each pin can have its own error type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<E> {
+    /// The I2C peripheral reported an error.
+    I2c(E),
+    /// A GPIO pin reported an error.
+    Pin(E),
+    /// The SPI peripheral reported an error.
+    Spi(E),
+    /// No device acknowledged the I2C address. The display is missing, not
+    /// powered or on a different address.
+    AddressNotAcknowledged,
+    /// The I2C device stopped acknowledging data bytes.
+    Nack,
+    /// A position is not on the display.
+    OutOfBounds,
+    /// A custom character slot is not in CGRAM.
+    InvalidCustomCharSlot,
+    /// A [PinMap](crate::data_bus::PinMap) uses a pin the port expander or
+    /// shift register does not have.
+    InvalidPinMap,
+    /// The display stayed busy for longer than any instruction takes.
+    Timeout,
+    /// The bus cannot read from the display.
+    Unsupported,
+}
+
+/// The kind of an [`Error`], independent of the bus error type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The bus or a pin reported an error.
+    Bus,
+    /// The I2C address or a data byte was not acknowledged.
+    NoAcknowledge,
+    /// A position is not on the display.
+    OutOfBounds,
+    /// A custom character slot is not in CGRAM.
+    InvalidCustomCharSlot,
+    /// A pin mapping does not fit the bus.
+    InvalidPinMap,
+    /// The display stayed busy for too long.
+    Timeout,
+    /// The operation is not supported by the bus.
+    Unsupported,
+}
+
+impl<E> Error<E> {
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::I2c(_) | Error::Pin(_) | Error::Spi(_) => ErrorKind::Bus,
+            Error::AddressNotAcknowledged | Error::Nack => ErrorKind::NoAcknowledge,
+            Error::OutOfBounds => ErrorKind::OutOfBounds,
+            Error::InvalidCustomCharSlot => ErrorKind::InvalidCustomCharSlot,
+            Error::InvalidPinMap => ErrorKind::InvalidPinMap,
+            Error::Timeout => ErrorKind::Timeout,
+            Error::Unsupported => ErrorKind::Unsupported,
+        }
+    }
+}
+
+impl<E: i2c::Error> Error<E> {
+    // Pull acknowledge failures out of the I2C error so they can be told apart
+    // from other bus errors without knowing the HAL
+    pub(crate) fn from_i2c(error: E) -> Self {
+        match error.kind() {
+            i2c::ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address) => {
+                Error::AddressNotAcknowledged
+            }
+            i2c::ErrorKind::NoAcknowledge(_) => Error::Nack,
+            _ => Error::I2c(error),
+        }
+    }
+}
+
+impl Error<digital::ErrorKind> {
+    pub(crate) fn from_pin<P: digital::Error>(error: P) -> Self {
+        Error::Pin(error.kind())
+    }
+}
+
+impl<E: fmt::Debug> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::I2c(error) => write!(f, "I2C error: {:?}", error),
+            Error::Pin(error) => write!(f, "pin error: {:?}", error),
+            Error::Spi(error) => write!(f, "SPI error: {:?}", error),
+            Error::AddressNotAcknowledged => f.write_str("I2C address not acknowledged"),
+            Error::Nack => f.write_str("I2C data not acknowledged"),
+            Error::OutOfBounds => f.write_str("position is not on the display"),
+            Error::InvalidCustomCharSlot => f.write_str("invalid custom character slot"),
+            Error::InvalidPinMap => f.write_str("pin map uses a pin the bus does not have"),
+            Error::Timeout => f.write_str("timed out waiting for the display"),
+            Error::Unsupported => f.write_str("operation not supported by the bus"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> core::error::Error for Error<E> {}
+
+impl<E: i2c::Error> i2c::Error for Error<E> {
+    fn kind(&self) -> i2c::ErrorKind {
+        match self {
+            Error::I2c(error) => error.kind(),
+            Error::AddressNotAcknowledged => {
+                i2c::ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)
+            }
+            Error::Nack => i2c::ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data),
+            _ => i2c::ErrorKind::Other,
+        }
+    }
+}
 
 /// result type for lcd display operations
-pub type Result<T> = core::result::Result<T, Error>;
+pub type Result<T, E> = core::result::Result<T, Error<E>>;
@@ -0,0 +1,204 @@
+use core::fmt;
+
+use crate::CharacterRom;
+
+/// A shadow copy of the visible characters of a display, `COLS` columns by `ROWS` rows.
+///
+/// Writes only change the buffer. [LCD1602::flush](crate::LCD1602::flush) then sends
+/// the cells that differ from what the display is showing, so redrawing a whole
+/// screen in which little has changed costs little bus traffic.
+///
+/// ```rust,ignore
+/// let mut frame = FrameBuffer::<16, 2>::new();
+/// frame.set_cursor(0, 1);
+/// write!(frame, "T={:.1}C", temperature)?;
+/// lcd.flush(&mut frame, &mut delay)?;
+/// ```
+///
+/// Text written with `write!` is converted with the buffer's own
+/// [character ROM](#method.set_character_rom), which is separate from the one of
+/// the display.
+#[derive(Clone)]
+pub struct FrameBuffer<const COLS: usize, const ROWS: usize> {
+    // What the display is showing
+    front: [[u8; COLS]; ROWS],
+    // What it should be showing after the next flush
+    back: [[u8; COLS]; ROWS],
+    column: usize,
+    row: usize,
+    rom: Option<&'static dyn CharacterRom>,
+    fallback: u8,
+}
+
+impl<const COLS: usize, const ROWS: usize> FrameBuffer<COLS, ROWS> {
+    /// Creates a buffer for a freshly cleared display, filled with spaces.
+    pub const fn new() -> FrameBuffer<COLS, ROWS> {
+        FrameBuffer {
+            front: [[b' '; COLS]; ROWS],
+            back: [[b' '; COLS]; ROWS],
+            column: 0,
+            row: 0,
+            rom: None,
+            fallback: b'?',
+        }
+    }
+
+    /// Set the character ROM used to convert text written with `write!`, see
+    /// [LCD1602::set_character_rom](crate::LCD1602::set_character_rom). Without
+    /// one, characters are downcast to a byte, so only ASCII shows up right.
+    pub fn set_character_rom(&mut self, rom: &'static dyn CharacterRom) {
+        self.rom = Some(rom);
+    }
+
+    /// Set the code written for characters the [character ROM](#method.set_character_rom)
+    /// has no glyph for. Defaults to `?`.
+    pub fn set_fallback_char(&mut self, code: u8) {
+        self.fallback = code;
+    }
+
+    /// Fills the buffer with spaces and moves the cursor to the first cell.
+    pub fn clear(&mut self) {
+        self.back = [[b' '; COLS]; ROWS];
+        self.column = 0;
+        self.row = 0;
+    }
+
+    /// Marks every cell as changed, so the next flush redraws the whole display.
+    /// Use this when the display has been written to without the buffer.
+    pub fn invalidate(&mut self) {
+        for (front, back) in self.front.iter_mut().zip(self.back.iter()) {
+            for (front, &back) in front.iter_mut().zip(back.iter()) {
+                // Any value other than the wanted one will do
+                *front = !back;
+            }
+        }
+    }
+
+    /// Moves the cursor used by [write_byte](#method.write_byte). Positions off
+    /// the buffer are clamped to its last cell.
+    pub fn set_cursor(&mut self, column: usize, row: usize) {
+        self.column = column.min(COLS.saturating_sub(1));
+        self.row = row.min(ROWS.saturating_sub(1));
+    }
+
+    /// Writes a byte at the cursor and moves the cursor on, wrapping at the end
+    /// of each row and from the last row back to the first.
+    pub fn write_byte(&mut self, data: u8) {
+        if COLS == 0 || ROWS == 0 {
+            return;
+        }
+
+        self.back[self.row][self.column] = data;
+
+        self.column += 1;
+        if self.column == COLS {
+            self.column = 0;
+            self.row = (self.row + 1) % ROWS;
+        }
+    }
+
+    /// Writes each byte of a string at the cursor, see [write_byte](#method.write_byte).
+    pub fn write_bytes(&mut self, string: &[u8]) {
+        for &b in string {
+            self.write_byte(b);
+        }
+    }
+
+    /// Sets a single cell. Cells off the buffer are ignored.
+    pub fn set(&mut self, column: usize, row: usize, data: u8) {
+        if let Some(cell) = self.back.get_mut(row).and_then(|row| row.get_mut(column)) {
+            *cell = data;
+        }
+    }
+
+    /// Returns the byte that a cell will show after the next flush.
+    pub fn get(&self, column: usize, row: usize) -> Option<u8> {
+        self.back.get(row).and_then(|row| row.get(column)).copied()
+    }
+
+    /// Returns whether any cell differs from what the display is showing.
+    pub fn is_dirty(&self) -> bool {
+        self.front != self.back
+    }
+
+    // The byte a cell still has to be sent, if it differs from the display
+    pub(crate) fn pending(&self, column: usize, row: usize) -> Option<u8> {
+        let back = self.back[row][column];
+
+        (self.front[row][column] != back).then_some(back)
+    }
+
+    pub(crate) fn mark_sent(&mut self, column: usize, row: usize) {
+        self.front[row][column] = self.back[row][column];
+    }
+}
+
+impl<const COLS: usize, const ROWS: usize> Default for FrameBuffer<COLS, ROWS> {
+    fn default() -> Self {
+        FrameBuffer::new()
+    }
+}
+
+impl<const COLS: usize, const ROWS: usize> fmt::Debug for FrameBuffer<COLS, ROWS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrameBuffer")
+            .field("front", &self.front)
+            .field("back", &self.back)
+            .field("column", &self.column)
+            .field("row", &self.row)
+            .field("fallback", &self.fallback)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<const COLS: usize, const ROWS: usize> fmt::Write for FrameBuffer<COLS, ROWS> {
+    fn write_str(&mut self, string: &str) -> fmt::Result {
+        for c in string.chars() {
+            let code = match self.rom {
+                Some(rom) => rom.encode(c).unwrap_or(self.fallback),
+                None => c as u8,
+            };
+            self.write_byte(code);
+
+            if let Some(mark) = self.rom.and_then(|rom| rom.mark(c)) {
+                self.write_byte(mark);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Write;
+
+    use super::*;
+    use crate::RomA02;
+
+    #[test]
+    fn write_goes_through_the_rom() {
+        let mut frame = FrameBuffer::<4, 1>::new();
+        frame.set_character_rom(&RomA02);
+        frame.set_fallback_char(b'*');
+        write!(frame, "aä中").unwrap();
+
+        assert_eq!(frame.get(0, 0), Some(b'a'));
+        assert_eq!(frame.get(1, 0), Some(0xE4));
+        assert_eq!(frame.get(2, 0), Some(b'*'));
+        assert_eq!(frame.get(3, 0), Some(b' '));
+    }
+
+    #[test]
+    fn pending_cells() {
+        let mut frame = FrameBuffer::<2, 2>::new();
+        frame.set(1, 1, b'x');
+
+        assert!(frame.is_dirty());
+        assert_eq!(frame.pending(0, 0), None);
+        assert_eq!(frame.pending(1, 1), Some(b'x'));
+
+        frame.mark_sent(1, 1);
+        assert!(!frame.is_dirty());
+    }
+}
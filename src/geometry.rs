@@ -0,0 +1,231 @@
+// Longest row a display can have, one line of DDRAM in 2-line mode. Helpers
+// that keep a copy of a row size it for this many cells.
+pub(crate) const MAX_COLUMNS: usize = 40;
+
+/// Size of a character display and the DDRAM address each of its rows starts at.
+///
+/// The controller always has 80 characters of DDRAM laid out as two lines of 40
+/// (0x00..=0x27 and 0x40..=0x67). Panels with more rows or fewer columns show a
+/// window of that memory, so the address of a visible cell depends on the panel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Geometry {
+    columns: u8,
+    rows: u8,
+    row_offsets: [u8; 4],
+    // Column at which a single physical row continues at 0x40 (16x1 "type 1")
+    split_column: Option<u8>,
+}
+
+impl Geometry {
+    /// 16 columns, 2 rows.
+    pub const LCD16X2: Geometry = Geometry::new(16, 2, [0x00, 0x40, 0x00, 0x00]);
+    /// 20 columns, 4 rows. Rows 3 and 4 continue rows 1 and 2 in DDRAM.
+    pub const LCD20X4: Geometry = Geometry::new(20, 4, [0x00, 0x40, 0x14, 0x54]);
+    /// 16 columns, 4 rows. Rows 3 and 4 continue rows 1 and 2 in DDRAM.
+    pub const LCD16X4: Geometry = Geometry::new(16, 4, [0x00, 0x40, 0x10, 0x50]);
+    /// 40 columns, 2 rows.
+    pub const LCD40X2: Geometry = Geometry::new(40, 2, [0x00, 0x40, 0x00, 0x00]);
+    /// 8 columns, 2 rows.
+    pub const LCD8X2: Geometry = Geometry::new(8, 2, [0x00, 0x40, 0x00, 0x00]);
+    /// 16 columns, 1 row, "type 1" panels. The left half of the row is
+    /// addressed from 0x00 and the right half from 0x40.
+    pub const LCD16X1: Geometry = Geometry {
+        columns: 16,
+        rows: 1,
+        row_offsets: [0x00, 0x00, 0x00, 0x00],
+        split_column: Some(8),
+    };
+    /// 16 columns, 1 row, "type 2" panels addressed linearly from 0x00.
+    pub const LCD16X1_TYPE2: Geometry = Geometry::new(16, 1, [0x00, 0x00, 0x00, 0x00]);
+    /// 40 columns, 4 rows, made of two controllers with an enable line each, see
+    /// [FourBitDualBus](crate::data_bus::FourBitDualBus). Rows 3 and 4 are rows 1
+    /// and 2 of the second controller, addressed with bit 7 set.
+    pub const LCD40X4: Geometry = Geometry::new(40, 4, [0x00, 0x40, 0x80, 0xC0]);
+
+    /// Creates a custom geometry from its size and the DDRAM address of the
+    /// first column of each row. Offsets past `rows` are ignored.
+    ///
+    /// ```rust,ignore
+    /// let geometry = Geometry::new(24, 2, [0x00, 0x40, 0x00, 0x00]);
+    /// ```
+    pub const fn new(columns: u8, rows: u8, row_offsets: [u8; 4]) -> Geometry {
+        Geometry {
+            columns,
+            rows,
+            row_offsets,
+            split_column: None,
+        }
+    }
+
+    /// Number of visible columns.
+    pub fn columns(&self) -> u8 {
+        self.columns
+    }
+
+    /// Number of visible rows.
+    pub fn rows(&self) -> u8 {
+        self.rows
+    }
+
+    /// Number of controllers driving the display, 2 if a row starts at an
+    /// address with bit 7 set and 1 otherwise.
+    pub fn controllers(&self) -> u8 {
+        let rows = self.rows.min(4) as usize;
+        if self.row_offsets[..rows].iter().any(|&offset| offset & 0x80 != 0) {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Returns the DDRAM address of a cell, or `None` if the cell is not on the
+    /// display or its address would be past 0xFF.
+    pub fn address(&self, column: u8, row: u8) -> Option<u8> {
+        if column >= self.columns || row >= self.rows || row >= 4 {
+            return None;
+        }
+
+        // A custom geometry can run past the last address
+        match self.split_column {
+            Some(split) if column >= split => 0x40u8.checked_add(column - split),
+            _ => self.row_offsets[row as usize].checked_add(column),
+        }
+    }
+
+    /// Returns the cell shown at a DDRAM address, or `None` if that address is
+    /// not visible on the display.
+    pub fn position(&self, address: u8) -> Option<(u8, u8)> {
+        for row in 0..self.rows.min(4) {
+            for column in 0..self.columns {
+                if self.address(column, row) == Some(address) {
+                    return Some((column, row));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl Default for Geometry {
+    /// The 16x2 layout of the LCD1602.
+    fn default() -> Self {
+        Geometry::LCD16X2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRESETS: [Geometry; 8] = [
+        Geometry::LCD16X2,
+        Geometry::LCD20X4,
+        Geometry::LCD16X4,
+        Geometry::LCD40X2,
+        Geometry::LCD8X2,
+        Geometry::LCD16X1,
+        Geometry::LCD16X1_TYPE2,
+        Geometry::LCD40X4,
+    ];
+
+    #[test]
+    fn position_inverts_address() {
+        for geometry in PRESETS {
+            for row in 0..geometry.rows() {
+                for column in 0..geometry.columns() {
+                    let address = geometry.address(column, row).unwrap();
+                    assert_eq!(
+                        geometry.position(address),
+                        Some((column, row)),
+                        "{geometry:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn address_out_of_bounds() {
+        for geometry in PRESETS {
+            assert_eq!(geometry.address(geometry.columns(), 0), None);
+            assert_eq!(geometry.address(0, geometry.rows()), None);
+        }
+    }
+
+    #[test]
+    fn row_starts() {
+        let starts = |geometry: Geometry| {
+            let mut starts = [None; 4];
+            for (row, start) in starts.iter_mut().enumerate() {
+                *start = geometry.address(0, row as u8);
+            }
+            starts
+        };
+
+        assert_eq!(
+            starts(Geometry::LCD16X2),
+            [Some(0x00), Some(0x40), None, None]
+        );
+        assert_eq!(
+            starts(Geometry::LCD20X4),
+            [Some(0x00), Some(0x40), Some(0x14), Some(0x54)]
+        );
+        assert_eq!(
+            starts(Geometry::LCD16X4),
+            [Some(0x00), Some(0x40), Some(0x10), Some(0x50)]
+        );
+        assert_eq!(
+            starts(Geometry::LCD40X4),
+            [Some(0x00), Some(0x40), Some(0x80), Some(0xC0)]
+        );
+    }
+
+    #[test]
+    fn split_row() {
+        let geometry = Geometry::LCD16X1;
+
+        assert_eq!(geometry.address(7, 0), Some(0x07));
+        assert_eq!(geometry.address(8, 0), Some(0x40));
+        assert_eq!(geometry.address(15, 0), Some(0x47));
+        assert_eq!(geometry.position(0x08), None);
+        assert_eq!(geometry.position(0x48), None);
+
+        assert_eq!(Geometry::LCD16X1_TYPE2.address(8, 0), Some(0x08));
+    }
+
+    #[test]
+    fn second_controller() {
+        let geometry = Geometry::LCD40X4;
+
+        assert_eq!(geometry.controllers(), 2);
+        assert_eq!(geometry.address(39, 2), Some(0xA7));
+        assert_eq!(geometry.address(39, 3), Some(0xE7));
+        assert_eq!(geometry.position(0x80), Some((0, 2)));
+        assert_eq!(geometry.position(0xC0), Some((0, 3)));
+        assert_eq!(geometry.position(0xA8), None);
+
+        for geometry in &PRESETS[..7] {
+            assert_eq!(geometry.controllers(), 1, "{geometry:?}");
+        }
+    }
+
+    #[test]
+    fn address_overflow() {
+        let geometry = Geometry::new(80, 1, [0xC0, 0x00, 0x00, 0x00]);
+
+        assert_eq!(geometry.address(0x3F, 0), Some(0xFF));
+        assert_eq!(geometry.address(0x40, 0), None);
+        assert_eq!(geometry.address(79, 0), None);
+        assert_eq!(geometry.position(0xFF), Some((0x3F, 0)));
+    }
+
+    #[test]
+    fn hidden_addresses() {
+        assert_eq!(Geometry::LCD16X2.position(0x10), None);
+        assert_eq!(Geometry::LCD16X2.position(0x27), None);
+        assert_eq!(Geometry::LCD16X2.position(0x68), None);
+        assert_eq!(Geometry::LCD20X4.position(0x27), Some((19, 2)));
+        assert_eq!(Geometry::LCD20X4.position(0x67), Some((19, 3)));
+    }
+}
@@ -0,0 +1,336 @@
+use embedded_hal::delay::DelayNs;
+
+use crate::data_bus::DataBus;
+use crate::error::Result;
+use crate::LCD1602;
+
+/// How a character of a [GlyphSet] is drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Glyph {
+    /// The character looks like a character of the ROM, e.g. Cyrillic `А` and Latin `A`.
+    Like(char),
+    /// A custom 5x8 character, in the row layout [LCD1602::create_char] takes.
+    Pixels([u8; 8]),
+}
+
+/// A set of characters that can be shown on a display whose ROM lacks them, by
+/// loading their glyphs into CGRAM when they are written.
+///
+/// ```rust,ignore
+/// lcd.set_glyph_sets(&[GlyphSet::CYRILLIC, GlyphSet::POLISH]);
+/// lcd.write_str("Привет", &mut delay)?;
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct GlyphSet {
+    glyphs: &'static [(char, Glyph)],
+}
+
+impl GlyphSet {
+    /// The Russian alphabet.
+    pub const CYRILLIC: GlyphSet = GlyphSet::new(CYRILLIC);
+    /// The Greek alphabet.
+    pub const GREEK: GlyphSet = GlyphSet::new(GREEK);
+    /// The Polish letters with diacritics.
+    pub const POLISH: GlyphSet = GlyphSet::new(POLISH);
+
+    /// Creates a glyph set from a table of characters and their glyphs.
+    pub const fn new(glyphs: &'static [(char, Glyph)]) -> GlyphSet {
+        GlyphSet { glyphs }
+    }
+
+    /// Returns the glyph of a character, or `None` if it is not in the set.
+    pub fn glyph(&self, c: char) -> Option<Glyph> {
+        self.glyphs
+            .iter()
+            .find(|&&(glyph, _)| glyph == c)
+            .map(|&(_, glyph)| glyph)
+    }
+}
+
+// State of one CGRAM slot
+#[derive(Clone, Copy, Debug, Default)]
+struct Slot {
+    // Character the slot was loaded with by the cache
+    glyph: Option<char>,
+    // Loaded through create_char, the cache never touches these
+    reserved: bool,
+    last_used: u32,
+    // DDRAM addresses currently showing the slot, per controller
+    cells: [u128; 2],
+}
+
+/// Keeps track of which character is in each of the 8 CGRAM slots, and where
+/// each slot is shown, so slots can be reused once their text is gone.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct GlyphCache {
+    slots: [Slot; 8],
+    clock: u32,
+}
+
+impl GlyphCache {
+    pub(crate) fn reserve(&mut self, slot: u8) {
+        self.slots[slot as usize] = Slot {
+            reserved: true,
+            ..Slot::default()
+        };
+    }
+
+    // Hand a slot back to the cache. It is only reused once it is no longer shown.
+    pub(crate) fn release(&mut self, slot: u8) {
+        let cells = self.slots[slot as usize].cells;
+        self.slots[slot as usize] = Slot {
+            cells,
+            ..Slot::default()
+        };
+    }
+
+    // A byte was written to DDRAM, codes 8 to 15 show the same slots as 0 to 7
+    pub(crate) fn written(&mut self, address: u8, code: u8) {
+        let controller = (address >> 7) as usize;
+        let cell = 1u128 << (address & 0x7F);
+
+        for slot in self.slots.iter_mut() {
+            slot.cells[controller] &= !cell;
+        }
+
+        if code < 16 {
+            self.slots[(code & 0x07) as usize].cells[controller] |= cell;
+        }
+    }
+
+    pub(crate) fn cleared(&mut self) {
+        for slot in self.slots.iter_mut() {
+            slot.cells = [0; 2];
+        }
+    }
+
+    fn find(&mut self, c: char) -> Option<u8> {
+        let index = self.slots.iter().position(|slot| slot.glyph == Some(c))?;
+        self.touch(index);
+
+        Some(index as u8)
+    }
+
+    fn touch(&mut self, index: usize) {
+        self.clock = self.clock.wrapping_add(1);
+        self.slots[index].last_used = self.clock;
+    }
+
+    // The slot to load a new glyph into: an empty one if there is any, and
+    // otherwise the least recently used one that is not on the display
+    fn victim(&self) -> Option<usize> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| !slot.reserved && slot.cells == [0; 2])
+            .min_by_key(|(_, slot)| (slot.glyph.is_some(), slot.last_used))
+            .map(|(index, _)| index)
+    }
+
+    fn load(&mut self, index: usize, c: char) {
+        self.slots[index].glyph = Some(c);
+        self.touch(index);
+    }
+}
+
+impl<B> LCD1602<B>
+where
+    B: DataBus,
+{
+    /// Set the glyph sets used for characters the [character ROM](#method.set_character_rom)
+    /// lacks. [write_str](#method.write_str), [write_char](#method.write_char) and the
+    /// [writer](#method.writer) load the glyph of such a character into a free CGRAM
+    /// slot, or the least recently used slot that is not on the display.
+    ///
+    /// Slots loaded with [create_char](#method.create_char) are left alone until they
+    /// are handed back with [release_char](#method.release_char). When every
+    /// other slot is showing on the display the [fallback character](#method.set_fallback_char)
+    /// is written instead.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_character_rom(&RomA00);
+    /// lcd.set_glyph_sets(&[GlyphSet::CYRILLIC]);
+    /// lcd.write_str("Привет", &mut delay)?;
+    /// ```
+    pub fn set_glyph_sets(&mut self, glyph_sets: &'static [GlyphSet]) {
+        self.glyph_sets = glyph_sets;
+    }
+
+    // The code of a glyph set character, loading it into CGRAM if needed, or
+    // `None` if no glyph set has the character
+    pub(crate) fn glyph_code<D: DelayNs>(
+        &mut self,
+        c: char,
+        delay: &mut D,
+    ) -> Result<Option<u8>, B::Error> {
+        let Some(glyph) = self.glyph_sets.iter().find_map(|set| set.glyph(c)) else {
+            return Ok(None);
+        };
+
+        let pixels = match glyph {
+            Glyph::Like(like) => {
+                let code = match self.rom {
+                    Some(rom) => rom.encode(like),
+                    None => like.is_ascii().then_some(like as u8),
+                };
+                return Ok(Some(code.unwrap_or(self.fallback)));
+            }
+            Glyph::Pixels(pixels) => pixels,
+        };
+
+        if let Some(slot) = self.glyph_cache.find(c) {
+            return Ok(Some(slot));
+        }
+
+        let Some(index) = self.glyph_cache.victim() else {
+            return Ok(Some(self.fallback));
+        };
+
+        self.write_cgram((index as u8) << 3, &pixels, delay)?;
+        self.glyph_cache.load(index, c);
+
+        Ok(Some(index as u8))
+    }
+}
+
+const CYRILLIC: &[(char, Glyph)] = &[
+    ('А', Glyph::Like('A')),
+    ('Б', Glyph::Pixels([0b11111, 0b10000, 0b10000, 0b11110, 0b10001, 0b10001, 0b11110, 0])),
+    ('В', Glyph::Like('B')),
+    ('Г', Glyph::Pixels([0b11111, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0])),
+    ('Д', Glyph::Pixels([0b00110, 0b01010, 0b01010, 0b01010, 0b01010, 0b11111, 0b10001, 0])),
+    ('Е', Glyph::Like('E')),
+    ('Ё', Glyph::Pixels([0b01010, 0b00000, 0b11111, 0b10000, 0b11110, 0b10000, 0b11111, 0])),
+    ('Ж', Glyph::Pixels([0b10101, 0b10101, 0b10101, 0b01110, 0b10101, 0b10101, 0b10101, 0])),
+    ('З', Glyph::Pixels([0b01110, 0b10001, 0b00001, 0b00110, 0b00001, 0b10001, 0b01110, 0])),
+    ('И', Glyph::Pixels([0b10001, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b10001, 0])),
+    ('Й', Glyph::Pixels([0b01010, 0b00100, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0])),
+    ('К', Glyph::Like('K')),
+    ('Л', Glyph::Pixels([0b00111, 0b01001, 0b01001, 0b01001, 0b01001, 0b01001, 0b10001, 0])),
+    ('М', Glyph::Like('M')),
+    ('Н', Glyph::Like('H')),
+    ('О', Glyph::Like('O')),
+    ('П', Glyph::Pixels([0b11111, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0])),
+    ('Р', Glyph::Like('P')),
+    ('С', Glyph::Like('C')),
+    ('Т', Glyph::Like('T')),
+    ('У', Glyph::Pixels([0b10001, 0b10001, 0b10001, 0b01111, 0b00001, 0b10001, 0b01110, 0])),
+    ('Ф', Glyph::Pixels([0b00100, 0b01110, 0b10101, 0b10101, 0b10101, 0b01110, 0b00100, 0])),
+    ('Х', Glyph::Like('X')),
+    ('Ц', Glyph::Pixels([0b10010, 0b10010, 0b10010, 0b10010, 0b10010, 0b10010, 0b11111, 0b00001])),
+    ('Ч', Glyph::Pixels([0b10001, 0b10001, 0b10001, 0b01111, 0b00001, 0b00001, 0b00001, 0])),
+    ('Ш', Glyph::Pixels([0b10101, 0b10101, 0b10101, 0b10101, 0b10101, 0b10101, 0b11111, 0])),
+    ('Щ', Glyph::Pixels([0b10101, 0b10101, 0b10101, 0b10101, 0b10101, 0b10101, 0b11111, 0b00001])),
+    ('Ъ', Glyph::Pixels([0b11000, 0b01000, 0b01000, 0b01110, 0b01001, 0b01001, 0b01110, 0])),
+    ('Ы', Glyph::Pixels([0b10001, 0b10001, 0b10001, 0b11001, 0b10101, 0b10101, 0b11001, 0])),
+    ('Ь', Glyph::Pixels([0b10000, 0b10000, 0b10000, 0b11110, 0b10001, 0b10001, 0b11110, 0])),
+    ('Э', Glyph::Pixels([0b01110, 0b10001, 0b00001, 0b00111, 0b00001, 0b10001, 0b01110, 0])),
+    ('Ю', Glyph::Pixels([0b10010, 0b10101, 0b10101, 0b11101, 0b10101, 0b10101, 0b10010, 0])),
+    ('Я', Glyph::Pixels([0b01111, 0b10001, 0b10001, 0b01111, 0b00101, 0b01001, 0b10001, 0])),
+    ('а', Glyph::Like('a')),
+    ('б', Glyph::Pixels([0b00011, 0b01100, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110, 0])),
+    ('в', Glyph::Pixels([0, 0, 0b11110, 0b10001, 0b11110, 0b10001, 0b11110, 0])),
+    ('г', Glyph::Pixels([0, 0, 0b11111, 0b10000, 0b10000, 0b10000, 0b10000, 0])),
+    ('д', Glyph::Pixels([0, 0, 0b00110, 0b01010, 0b01010, 0b11111, 0b10001, 0])),
+    ('е', Glyph::Like('e')),
+    ('ё', Glyph::Pixels([0b01010, 0, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110, 0])),
+    ('ж', Glyph::Pixels([0, 0, 0b10101, 0b10101, 0b01110, 0b10101, 0b10101, 0])),
+    ('з', Glyph::Pixels([0, 0, 0b01110, 0b10001, 0b00110, 0b10001, 0b01110, 0])),
+    ('и', Glyph::Pixels([0, 0, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0])),
+    ('й', Glyph::Pixels([0b01010, 0b00100, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0])),
+    ('к', Glyph::Pixels([0, 0, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0])),
+    ('л', Glyph::Pixels([0, 0, 0b00111, 0b01001, 0b01001, 0b01001, 0b10001, 0])),
+    ('м', Glyph::Pixels([0, 0, 0b10001, 0b11011, 0b10101, 0b10001, 0b10001, 0])),
+    ('н', Glyph::Pixels([0, 0, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0])),
+    ('о', Glyph::Like('o')),
+    ('п', Glyph::Pixels([0, 0, 0b11111, 0b10001, 0b10001, 0b10001, 0b10001, 0])),
+    ('р', Glyph::Like('p')),
+    ('с', Glyph::Like('c')),
+    ('т', Glyph::Pixels([0, 0, 0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0])),
+    ('у', Glyph::Like('y')),
+    ('ф', Glyph::Pixels([0, 0b00100, 0b01110, 0b10101, 0b10101, 0b01110, 0b00100, 0])),
+    ('х', Glyph::Like('x')),
+    ('ц', Glyph::Pixels([0, 0, 0b10010, 0b10010, 0b10010, 0b10010, 0b11111, 0b00001])),
+    ('ч', Glyph::Pixels([0, 0, 0b10001, 0b10001, 0b01111, 0b00001, 0b00001, 0])),
+    ('ш', Glyph::Pixels([0, 0, 0b10101, 0b10101, 0b10101, 0b10101, 0b11111, 0])),
+    ('щ', Glyph::Pixels([0, 0, 0b10101, 0b10101, 0b10101, 0b10101, 0b11111, 0b00001])),
+    ('ъ', Glyph::Pixels([0, 0, 0b11000, 0b01000, 0b01110, 0b01001, 0b01110, 0])),
+    ('ы', Glyph::Pixels([0, 0, 0b10001, 0b10001, 0b11001, 0b10101, 0b11001, 0])),
+    ('ь', Glyph::Pixels([0, 0, 0b10000, 0b10000, 0b11110, 0b10001, 0b11110, 0])),
+    ('э', Glyph::Pixels([0, 0, 0b01110, 0b10001, 0b00111, 0b10001, 0b01110, 0])),
+    ('ю', Glyph::Pixels([0, 0, 0b10010, 0b10101, 0b11101, 0b10101, 0b10010, 0])),
+    ('я', Glyph::Pixels([0, 0, 0b01111, 0b10001, 0b01111, 0b00101, 0b01001, 0])),
+];
+
+const GREEK: &[(char, Glyph)] = &[
+    ('Α', Glyph::Like('A')),
+    ('Β', Glyph::Like('B')),
+    ('Γ', Glyph::Pixels([0b11111, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0])),
+    ('Δ', Glyph::Pixels([0b00100, 0b00100, 0b01010, 0b01010, 0b10001, 0b10001, 0b11111, 0])),
+    ('Ε', Glyph::Like('E')),
+    ('Ζ', Glyph::Like('Z')),
+    ('Η', Glyph::Like('H')),
+    ('Θ', Glyph::Pixels([0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b01110, 0])),
+    ('Ι', Glyph::Like('I')),
+    ('Κ', Glyph::Like('K')),
+    ('Λ', Glyph::Pixels([0b00100, 0b01010, 0b01010, 0b10001, 0b10001, 0b10001, 0b10001, 0])),
+    ('Μ', Glyph::Like('M')),
+    ('Ν', Glyph::Like('N')),
+    ('Ξ', Glyph::Pixels([0b11111, 0, 0, 0b01110, 0, 0, 0b11111, 0])),
+    ('Ο', Glyph::Like('O')),
+    ('Π', Glyph::Pixels([0b11111, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0])),
+    ('Ρ', Glyph::Like('P')),
+    ('Σ', Glyph::Pixels([0b11111, 0b10000, 0b01000, 0b00100, 0b01000, 0b10000, 0b11111, 0])),
+    ('Τ', Glyph::Like('T')),
+    ('Υ', Glyph::Like('Y')),
+    ('Φ', Glyph::Pixels([0b00100, 0b01110, 0b10101, 0b10101, 0b10101, 0b01110, 0b00100, 0])),
+    ('Χ', Glyph::Like('X')),
+    ('Ψ', Glyph::Pixels([0b10101, 0b10101, 0b10101, 0b01110, 0b00100, 0b00100, 0b00100, 0])),
+    ('Ω', Glyph::Pixels([0b01110, 0b10001, 0b10001, 0b10001, 0b01010, 0b01010, 0b11011, 0])),
+    ('α', Glyph::Pixels([0, 0, 0b01001, 0b10101, 0b10010, 0b10010, 0b01101, 0])),
+    ('β', Glyph::Pixels([0, 0b01110, 0b10001, 0b11110, 0b10001, 0b11110, 0b10000, 0b10000])),
+    ('γ', Glyph::Pixels([0, 0, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100, 0])),
+    ('δ', Glyph::Pixels([0b01110, 0b01000, 0b00100, 0b01110, 0b10001, 0b10001, 0b01110, 0])),
+    ('ε', Glyph::Pixels([0, 0, 0b01110, 0b10000, 0b01100, 0b10000, 0b01110, 0])),
+    ('ζ', Glyph::Pixels([0b11110, 0b00100, 0b01000, 0b10000, 0b10000, 0b01110, 0b00001, 0b00110])),
+    ('η', Glyph::Pixels([0, 0, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001, 0b00001])),
+    ('θ', Glyph::Pixels([0, 0b00110, 0b01001, 0b01111, 0b01001, 0b01001, 0b00110, 0])),
+    ('ι', Glyph::Pixels([0, 0, 0b00100, 0b00100, 0b00100, 0b00100, 0b00010, 0])),
+    ('κ', Glyph::Like('k')),
+    ('λ', Glyph::Pixels([0b01000, 0b00100, 0b00100, 0b01010, 0b01010, 0b10001, 0b10001, 0])),
+    ('μ', Glyph::Pixels([0, 0, 0b10001, 0b10001, 0b10001, 0b11011, 0b10101, 0b10000])),
+    ('ν', Glyph::Like('v')),
+    ('ξ', Glyph::Pixels([0b01110, 0b10000, 0b01100, 0b10000, 0b01110, 0b00001, 0b00110, 0])),
+    ('ο', Glyph::Like('o')),
+    ('π', Glyph::Pixels([0, 0, 0b11111, 0b01010, 0b01010, 0b01010, 0b10011, 0])),
+    ('ρ', Glyph::Pixels([0, 0, 0b01110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000])),
+    ('σ', Glyph::Pixels([0, 0, 0b01111, 0b10010, 0b10001, 0b10001, 0b01110, 0])),
+    ('ς', Glyph::Pixels([0, 0, 0b01111, 0b10000, 0b01110, 0b00001, 0b00110, 0])),
+    ('τ', Glyph::Pixels([0, 0, 0b11111, 0b00100, 0b00100, 0b00100, 0b00011, 0])),
+    ('υ', Glyph::Like('u')),
+    ('φ', Glyph::Pixels([0, 0b00100, 0b01110, 0b10101, 0b10101, 0b01110, 0b00100, 0])),
+    ('χ', Glyph::Like('x')),
+    ('ψ', Glyph::Pixels([0, 0, 0b10101, 0b10101, 0b10101, 0b01110, 0b00100, 0b00100])),
+    ('ω', Glyph::Pixels([0, 0, 0b01010, 0b10001, 0b10101, 0b10101, 0b01010, 0])),
+];
+
+const POLISH: &[(char, Glyph)] = &[
+    ('Ą', Glyph::Pixels([0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001, 0b00010])),
+    ('Ć', Glyph::Pixels([0b00010, 0b00100, 0b01110, 0b10001, 0b10000, 0b10001, 0b01110, 0])),
+    ('Ę', Glyph::Pixels([0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111, 0b00010])),
+    ('Ł', Glyph::Pixels([0b10000, 0b10000, 0b10010, 0b10100, 0b11000, 0b10000, 0b11111, 0])),
+    ('Ń', Glyph::Pixels([0b00010, 0b00100, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0])),
+    ('Ó', Glyph::Pixels([0b00010, 0b00100, 0b01110, 0b10001, 0b10001, 0b10001, 0b01110, 0])),
+    ('Ś', Glyph::Pixels([0b00010, 0b00100, 0b01111, 0b10000, 0b01110, 0b00001, 0b11110, 0])),
+    ('Ź', Glyph::Pixels([0b00010, 0b00100, 0b11111, 0b00010, 0b00100, 0b01000, 0b11111, 0])),
+    ('Ż', Glyph::Pixels([0b00100, 0, 0b11111, 0b00010, 0b00100, 0b01000, 0b11111, 0])),
+    ('ą', Glyph::Pixels([0, 0, 0b01110, 0b00001, 0b01111, 0b10001, 0b01111, 0b00010])),
+    ('ć', Glyph::Pixels([0b00010, 0b00100, 0b01110, 0b10000, 0b10000, 0b10001, 0b01110, 0])),
+    ('ę', Glyph::Pixels([0, 0, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110, 0b00010])),
+    ('ł', Glyph::Pixels([0b01100, 0b00100, 0b00110, 0b01100, 0b00100, 0b00100, 0b01110, 0])),
+    ('ń', Glyph::Pixels([0b00010, 0b00100, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001, 0])),
+    ('ó', Glyph::Pixels([0b00010, 0b00100, 0b01110, 0b10001, 0b10001, 0b10001, 0b01110, 0])),
+    ('ś', Glyph::Pixels([0b00010, 0b00100, 0b01110, 0b10000, 0b01110, 0b00001, 0b11110, 0])),
+    ('ź', Glyph::Pixels([0b00010, 0b00100, 0b11111, 0b00010, 0b00100, 0b01000, 0b11111, 0])),
+    ('ż', Glyph::Pixels([0b00100, 0, 0b11111, 0b00010, 0b00100, 0b01000, 0b11111, 0])),
+];
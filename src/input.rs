@@ -0,0 +1,479 @@
+use embedded_hal::delay::DelayNs;
+
+use crate::data_bus::DataBus;
+use crate::display_control::CursorBlink;
+use crate::error::Result;
+use crate::geometry::MAX_COLUMNS;
+use crate::progress_bar::update_row;
+use crate::LCD1602;
+
+/// Space, letters, digits and ASCII punctuation, the default character set of
+/// a [TextInput].
+pub const PRINTABLE: &[u8] = b" ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789!\"#$%&'()*+,-./:;<=>?@[]^_{|}";
+
+/// Space, upper case letters and digits.
+pub const ALPHANUMERIC: &[u8] = b" ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// Button presses the input widgets react to, fed in by the application from
+/// its own button or encoder handling.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputEvent {
+    /// Increase the value under the cursor
+    Up,
+    /// Decrease the value under the cursor
+    Down,
+    /// Move the cursor left
+    Left,
+    /// Move the cursor right
+    Right,
+    /// Accept the value
+    Select,
+}
+
+/// A number edited one digit at a time, shown with leading zeros. The digit
+/// being edited is marked by the blinking cursor of the display.
+///
+/// ```rust,ignore
+/// let mut setpoint = NumberInput::new(10, 1, 215, 50, 300);
+/// loop {
+///     setpoint.draw(&mut lcd, &mut delay)?;
+///     if let Some(value) = setpoint.handle(buttons.wait_event()) {
+///         setpoint.close(&mut lcd, &mut delay)?;
+///         break value;
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct NumberInput {
+    column: u8,
+    row: u8,
+    value: i32,
+    min: i32,
+    max: i32,
+    step: i32,
+    digits: u8,
+    // Digit being edited, counted from the right
+    position: u8,
+    cells: [u8; MAX_COLUMNS],
+    drawn: bool,
+}
+
+impl NumberInput {
+    /// Creates a field for a value between `min` and `max` at a column and row
+    /// of the display, with the last digit selected. The field is as wide as
+    /// the longest value, plus a column for the sign if `min` is negative.
+    pub fn new(column: u8, row: u8, value: i32, min: i32, max: i32) -> NumberInput {
+        let (min, max) = (min.min(max), min.max(max));
+        let longest = min.unsigned_abs().max(max.unsigned_abs());
+
+        NumberInput {
+            column,
+            row,
+            value: value.clamp(min, max),
+            min,
+            max,
+            step: 1,
+            digits: longest.checked_ilog10().unwrap_or(0) as u8 + 1,
+            position: 0,
+            cells: [b' '; MAX_COLUMNS],
+            drawn: false,
+        }
+    }
+
+    /// Sets the smallest change `Up` and `Down` make. On digits whose place
+    /// value is larger they change the value by the place value instead.
+    ///
+    /// Default is `1`.
+    pub fn set_step(&mut self, step: i32) -> &mut Self {
+        self.step = step.max(1);
+        self
+    }
+
+    /// Returns the value being edited.
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    /// Makes the next draw rewrite every cell, for when the display has been
+    /// cleared or written over.
+    pub fn invalidate(&mut self) {
+        self.drawn = false;
+    }
+
+    /// Updates the value for a button press, and returns it once accepted with
+    /// `Select`. Values are kept between the minimum and maximum.
+    pub fn handle(&mut self, event: InputEvent) -> Option<i32> {
+        let delta = 10i64.pow(self.position as u32).max(self.step as i64);
+
+        let value = match event {
+            InputEvent::Up => self.value as i64 + delta,
+            InputEvent::Down => self.value as i64 - delta,
+            InputEvent::Left => {
+                self.position = (self.position + 1).min(self.digits - 1);
+                return None;
+            }
+            InputEvent::Right => {
+                self.position = self.position.saturating_sub(1);
+                return None;
+            }
+            InputEvent::Select => return Some(self.value),
+        };
+
+        self.value = value.clamp(self.min as i64, self.max as i64) as i32;
+
+        None
+    }
+
+    /// Shows the value and puts the blinking cursor on the digit being edited.
+    ///
+    /// Returns an error if the field does not fit on the display.
+    pub fn draw<B: DataBus, D: DelayNs>(
+        &mut self,
+        lcd: &mut LCD1602<B>,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        let signed = self.min < 0;
+        let width = signed as usize + self.digits as usize;
+
+        let mut cells = [b'0'; MAX_COLUMNS];
+        if signed {
+            cells[0] = if self.value < 0 { b'-' } else { b' ' };
+        }
+
+        let mut rest = self.value.unsigned_abs();
+        for cell in cells[signed as usize..width].iter_mut().rev() {
+            *cell = b'0' + (rest % 10) as u8;
+            rest /= 10;
+        }
+
+        update_row(
+            lcd,
+            self.column,
+            self.row,
+            &cells[..width],
+            &mut self.cells[..width],
+            !self.drawn,
+            delay,
+        )?;
+        self.drawn = true;
+
+        let edit = self.column as usize + width - 1 - self.position as usize;
+        show_cursor(lcd, edit as u8, self.row, delay)
+    }
+
+    /// Stops the cursor blinking once editing is done.
+    pub fn close<B: DataBus, D: DelayNs>(
+        &mut self,
+        lcd: &mut LCD1602<B>,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        lcd.set_cursor_blink(CursorBlink::Off, delay)
+    }
+}
+
+/// A line of text edited one character at a time by stepping through a
+/// character set, such as a network name. The character being edited is
+/// marked by the blinking cursor of the display.
+///
+/// The field is `N` cells wide. Trailing spaces are not part of the text.
+///
+/// ```rust,ignore
+/// let mut ssid: TextInput<16> = TextInput::new(0, 1);
+/// ssid.set_text(b"home");
+/// loop {
+///     ssid.draw(&mut lcd, &mut delay)?;
+///     if let Some(name) = ssid.handle(buttons.wait_event()) {
+///         wifi.connect(name);
+///         break;
+///     }
+/// }
+/// ssid.close(&mut lcd, &mut delay)?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct TextInput<const N: usize> {
+    column: u8,
+    row: u8,
+    charset: &'static [u8],
+    text: [u8; N],
+    position: usize,
+    cells: [u8; N],
+    drawn: bool,
+}
+
+impl<const N: usize> TextInput<N> {
+    /// Creates an empty field at a column and row of the display, with the
+    /// first character selected.
+    pub fn new(column: u8, row: u8) -> TextInput<N> {
+        TextInput {
+            column,
+            row,
+            charset: PRINTABLE,
+            text: [b' '; N],
+            position: 0,
+            cells: [b' '; N],
+            drawn: false,
+        }
+    }
+
+    /// Sets the character codes `Up` and `Down` step through, in order. They
+    /// are written as they are, so custom character slots work as well.
+    ///
+    /// Default is [PRINTABLE].
+    pub fn set_charset(&mut self, charset: &'static [u8]) -> &mut Self {
+        self.charset = charset;
+        self
+    }
+
+    /// Replaces the text with character codes, like those of the
+    /// [charset](#method.set_charset), cut down to `N` codes, and selects the
+    /// first character.
+    pub fn set_text(&mut self, text: &[u8]) -> &mut Self {
+        self.text = [b' '; N];
+        for (cell, &code) in self.text.iter_mut().zip(text) {
+            *cell = code;
+        }
+        self.position = 0;
+        self
+    }
+
+    /// Returns the text being edited, without trailing spaces.
+    pub fn text(&self) -> &[u8] {
+        let len = self
+            .text
+            .iter()
+            .rposition(|&c| c != b' ')
+            .map_or(0, |last| last + 1);
+        &self.text[..len]
+    }
+
+    /// Makes the next draw rewrite every cell, for when the display has been
+    /// cleared or written over.
+    pub fn invalidate(&mut self) {
+        self.drawn = false;
+    }
+
+    /// Updates the text for a button press, and returns it once accepted with
+    /// `Select`.
+    pub fn handle(&mut self, event: InputEvent) -> Option<&[u8]> {
+        let count = self.charset.len();
+
+        match event {
+            InputEvent::Up | InputEvent::Down if count > 0 && N > 0 => {
+                let cell = &mut self.text[self.position];
+                let next = match self.charset.iter().position(|c| c == cell) {
+                    Some(index) if event == InputEvent::Up => (index + 1) % count,
+                    Some(index) => (index + count - 1) % count,
+                    None => 0,
+                };
+                *cell = self.charset[next];
+            }
+            InputEvent::Left => self.position = self.position.saturating_sub(1),
+            InputEvent::Right => self.position = (self.position + 1).min(N.saturating_sub(1)),
+            InputEvent::Select => return Some(self.text()),
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Shows the text and puts the blinking cursor on the character being
+    /// edited.
+    ///
+    /// Returns an error if the field does not fit on the display.
+    pub fn draw<B: DataBus, D: DelayNs>(
+        &mut self,
+        lcd: &mut LCD1602<B>,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        update_row(
+            lcd,
+            self.column,
+            self.row,
+            &self.text,
+            &mut self.cells,
+            !self.drawn,
+            delay,
+        )?;
+        self.drawn = true;
+
+        let edit = self.column as usize + self.position;
+        show_cursor(lcd, edit as u8, self.row, delay)
+    }
+
+    /// Stops the cursor blinking once editing is done.
+    pub fn close<B: DataBus, D: DelayNs>(
+        &mut self,
+        lcd: &mut LCD1602<B>,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        lcd.set_cursor_blink(CursorBlink::Off, delay)
+    }
+}
+
+/// A choice between two answers, shown side by side with the blinking cursor
+/// of the display on the chosen one. `No` is chosen at first.
+///
+/// ```rust,ignore
+/// lcd.write_str("Reset settings?", &mut delay)?;
+/// let mut prompt = Confirm::new(0, 1);
+/// let reset = loop {
+///     prompt.draw(&mut lcd, &mut delay)?;
+///     if let Some(yes) = prompt.handle(buttons.wait_event()) {
+///         break yes;
+///     }
+/// };
+/// prompt.close(&mut lcd, &mut delay)?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct Confirm<'a> {
+    column: u8,
+    row: u8,
+    yes: &'a str,
+    no: &'a str,
+    choice: bool,
+    cells: [u8; MAX_COLUMNS],
+    // Cells the answers took up when last drawn
+    width: usize,
+    drawn: bool,
+}
+
+impl<'a> Confirm<'a> {
+    /// Creates a `Yes`/`No` prompt at a column and row of the display.
+    pub fn new(column: u8, row: u8) -> Confirm<'a> {
+        Confirm {
+            column,
+            row,
+            yes: "Yes",
+            no: "No",
+            choice: false,
+            cells: [b' '; MAX_COLUMNS],
+            width: 0,
+            drawn: false,
+        }
+    }
+
+    /// Replaces the answers shown, e.g. for another language. Cells of longer
+    /// answers drawn before are blanked on the next draw.
+    pub fn set_labels(&mut self, yes: &'a str, no: &'a str) -> &mut Self {
+        self.yes = yes;
+        self.no = no;
+        self.drawn = false;
+        self
+    }
+
+    /// Returns whether `Yes` is chosen.
+    pub fn choice(&self) -> bool {
+        self.choice
+    }
+
+    /// Makes the next draw rewrite every cell, for when the display has been
+    /// cleared or written over.
+    pub fn invalidate(&mut self) {
+        self.drawn = false;
+    }
+
+    /// Updates the choice for a button press, and returns it once accepted
+    /// with `Select`. `Left` chooses `Yes`, `Right` chooses `No`, and `Up`
+    /// and `Down` switch between them.
+    pub fn handle(&mut self, event: InputEvent) -> Option<bool> {
+        match event {
+            InputEvent::Up | InputEvent::Down => self.choice = !self.choice,
+            InputEvent::Left => self.choice = true,
+            InputEvent::Right => self.choice = false,
+            InputEvent::Select => return Some(self.choice),
+        }
+
+        None
+    }
+
+    /// Shows both answers and puts the blinking cursor on the chosen one.
+    ///
+    /// Returns an error if the prompt does not fit on the display.
+    pub fn draw<B: DataBus, D: DelayNs>(
+        &mut self,
+        lcd: &mut LCD1602<B>,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        let mut cells = [b' '; MAX_COLUMNS];
+        let mut width = 0;
+        let mut no_column = 0;
+
+        for (index, label) in [self.yes, self.no].into_iter().enumerate() {
+            if index == 1 {
+                // One blank between the answers
+                width = (width + 1).min(MAX_COLUMNS);
+                no_column = width;
+            }
+
+            width += lcd.encode_cells(label.chars(), &mut cells[width..], delay)?;
+        }
+
+        // Blank what is left of longer answers drawn before
+        let drawn_width = width.max(self.width);
+        update_row(
+            lcd,
+            self.column,
+            self.row,
+            &cells[..drawn_width],
+            &mut self.cells[..drawn_width],
+            !self.drawn,
+            delay,
+        )?;
+        self.width = width;
+        self.drawn = true;
+
+        let edit = if self.choice { 0 } else { no_column };
+        show_cursor(lcd, self.column + edit as u8, self.row, delay)
+    }
+
+    /// Stops the cursor blinking once editing is done.
+    pub fn close<B: DataBus, D: DelayNs>(
+        &mut self,
+        lcd: &mut LCD1602<B>,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        lcd.set_cursor_blink(CursorBlink::Off, delay)
+    }
+}
+
+// Put the blinking cursor on a cell. The underline cursor is left as it is.
+fn show_cursor<B: DataBus, D: DelayNs>(
+    lcd: &mut LCD1602<B>,
+    column: u8,
+    row: u8,
+    delay: &mut D,
+) -> Result<(), B::Error> {
+    lcd.set_cursor(column, row, delay)?;
+    lcd.set_cursor_blink(CursorBlink::On, delay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_bus::NoDelay;
+    use crate::Geometry;
+
+    #[test]
+    fn shorter_labels_blank_the_rest() {
+        let geometry = Geometry::LCD16X2;
+        let mut lcd = LCD1602::test(geometry);
+        let mut prompt = Confirm::new(0, 1);
+        prompt.set_labels("Confirm", "Cancel");
+        prompt.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(&lcd.bus().row(geometry, 1)[..16], b"Confirm Cancel  ");
+
+        prompt.set_labels("Ja", "Nein");
+        prompt.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(&lcd.bus().row(geometry, 1)[..16], b"Ja Nein         ");
+    }
+
+    #[test]
+    fn set_text_takes_codes() {
+        let mut input: TextInput<4> = TextInput::new(0, 0);
+        input.set_text(&[b'a', 0xE4, 0x01, b'b', b'c']);
+        assert_eq!(input.text(), &[b'a', 0xE4, 0x01, b'b']);
+
+        input.set_text(b"x");
+        assert_eq!(input.text(), b"x");
+    }
+}
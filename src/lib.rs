@@ -16,9 +16,12 @@ extern crate bitflags;
 /// The types of bus that can be used to communicate with the display.
 pub mod data_bus;
 
-use core::ops::Deref;
 use embedded_hal::delay::DelayNs;
-use data_bus::{DataBus, EightBitBus, FourBitBus, I2CBus};
+use data_bus::{
+    Backlight, Controller, DataBus, EightBitBus, EightBitDualBus, EightBitRwBus, FourBitBus,
+    FourBitDualBus, FourBitRwBus, GpioShiftRegister, I2CBus, Mcp230xx, Mcp230xxBus, PinMap,
+    ShiftOut, ShiftRegisterBus, SpiShiftRegister,
+};
 
 /// Display module for 16x2 LCD displays
 pub mod display_control;
@@ -30,10 +33,83 @@ use entry_mode::{CursorMode, EntryMode, ShiftMode};
 
 /// Error types
 pub mod error;
-use error::Result;
 
-use embedded_hal::digital::OutputPin;
+mod command;
+
+#[cfg(test)]
+mod test_bus;
+
+/// Async driver on top of `embedded-hal-async`
+#[cfg(feature = "async")]
+pub mod asynch;
+#[cfg(feature = "async")]
+pub use asynch::AsyncLCD1602;
+
+/// Display sizes and their DDRAM layout
+pub mod geometry;
+pub use geometry::Geometry;
+
+/// `core::fmt::Write` support
+pub mod writer;
+pub use writer::Writer;
+
+/// A driver that owns its delay provider
+pub mod with_delay;
+pub use with_delay::LCD1602WithDelay;
+
+/// Shadow buffer of the display contents
+pub mod frame_buffer;
+pub use frame_buffer::FrameBuffer;
+
+/// Control characters and line wrapping for text
+pub mod text_mode;
+pub use text_mode::TextMode;
+use text_mode::DdramShadow;
+
+/// Character generator ROMs
+pub mod character_rom;
+pub use character_rom::{CharacterRom, RomA00, RomA02};
+
+/// Characters loaded into CGRAM on demand
+pub mod glyph_cache;
+pub use glyph_cache::{Glyph, GlyphSet};
+use glyph_cache::GlyphCache;
+
+/// Large digits drawn with custom characters
+pub mod big_digits;
+pub use big_digits::BigFont;
+
+/// Horizontal bar widget
+pub mod progress_bar;
+pub use progress_bar::ProgressBar;
+
+/// Bar graph of recent samples
+pub mod sparkline;
+pub use sparkline::{Scale, Sparkline};
+
+/// Scrolling text in part of a row
+pub mod marquee;
+pub use marquee::{Marquee, MarqueeMode};
+
+/// Menus driven by button events
+pub mod menu;
+pub use menu::{Menu, MenuAction, MenuEvent, MenuItem};
+
+/// Widgets for editing a value in place
+pub mod input;
+pub use input::{Confirm, InputEvent, NumberInput, TextInput};
+
+/// Number formatting without `core::fmt`
+pub mod number_format;
+pub use number_format::Align;
+
+/// Writing and blanking whole rows
+pub mod line;
+use error::{Error, Result};
+
+use embedded_hal::digital::{ErrorKind, InputPin, OutputPin};
 use embedded_hal::i2c::I2c;
+use embedded_hal::spi::SpiDevice;
 
 /**
 Handles all the logic related to working with the character LCD via I2C. You'll
@@ -44,6 +120,22 @@ pub struct LCD1602<B: DataBus> {
     bus: B,
     entry_mode: EntryMode,
     display_mode: DisplayMode,
+    geometry: Geometry,
+    // Software copy of the DDRAM address counter
+    address: u8,
+    text_mode: TextMode,
+    // Row whose last column was just written in text mode, the next
+    // character goes on the next row
+    wrap_pending: Option<u8>,
+    rom: Option<&'static dyn CharacterRom>,
+    fallback: u8,
+    glyph_sets: &'static [GlyphSet],
+    glyph_cache: GlyphCache,
+    decimal_separator: u8,
+    // Copy of DDRAM for scrolling in text mode
+    ddram: DdramShadow,
+    // Last function set instruction sent, for changing the font
+    function_set: u8,
 }
 
 /// Used in the direction argument for shifting the cursor and the display
@@ -54,6 +146,18 @@ pub enum Direction {
     Right,
 }
 
+/// Character font of the display, see [LCD1602::set_font].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Font {
+    /// 5x8 dots with two lines of DDRAM, what displays are set up with.
+    #[default]
+    Dots5x8,
+    /// 5x10 dots. The controller only has this font with one line of DDRAM
+    /// from 0x00 to 0x4F, so it suits single row displays addressed linearly
+    /// such as [Geometry::LCD16X1_TYPE2].
+    Dots5x10,
+}
+
 impl<
         RS: OutputPin,
         EN: OutputPin,
@@ -70,14 +174,15 @@ impl<
     /// Create an instance of a `LCD1602` from 8 data pins, a register select
     /// pin, an enable pin and a struct implementing the delay trait.
     /// - The delay instance is used to sleep between commands to
-    /// ensure the `LCD1602` has enough time to process commands.
+    ///   ensure the `LCD1602` has enough time to process commands.
     /// - The eight db0..db7 pins are used to send and recieve with
-    ///  the `LCD1602`.
+    ///   the `LCD1602`.
     /// - The register select pin is used to tell the `LCD1602`
-    /// if incoming data is a command or data.
+    ///   if incoming data is a command or data.
     /// - The enable pin is used to tell the `LCD1602` that there
-    /// is data on the 8 data pins and that it should read them in.
+    ///   is data on the 8 data pins and that it should read them in.
     ///
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
     pub fn new_8bit<D: DelayNs>(
         rs: RS,
         en: EN,
@@ -90,16 +195,13 @@ impl<
         d6: D6,
         d7: D7,
         delay: &mut D,
-    ) -> Result<LCD1602<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>>> {
-        let mut hd = LCD1602 {
-            bus: EightBitBus::from_pins(rs, en, d0, d1, d2, d3, d4, d5, d6, d7),
-            entry_mode: EntryMode::default(),
-            display_mode: DisplayMode::default(),
-        };
+    ) -> Result<LCD1602<EightBitBus<RS, EN, D0, D1, D2, D3, D4, D5, D6, D7>>, ErrorKind> {
+        let bus = EightBitBus::from_pins(rs, en, d0, d1, d2, d3, d4, d5, d6, d7);
+        let mut hd = LCD1602::from_parts(bus, Geometry::default());
 
         hd.init_8bit(delay)?;
 
-        return Ok(hd);
+        Ok(hd)
     }
 }
 
@@ -109,13 +211,13 @@ impl<RS: OutputPin, EN: OutputPin, D4: OutputPin, D5: OutputPin, D6: OutputPin,
     /// Create an instance of a `LCD1602` from 4 data pins, a register select
     /// pin, an enable pin and a struct implementing the delay trait.
     /// - The delay instance is used to sleep between commands to
-    /// ensure the `LCD1602` has enough time to process commands.
+    ///   ensure the `LCD1602` has enough time to process commands.
     /// - The four db0..db3 pins are used to send and recieve with
-    ///  the `LCD1602`.
+    ///   the `LCD1602`.
     /// - The register select pin is used to tell the `LCD1602`
-    /// if incoming data is a command or data.
+    ///   if incoming data is a command or data.
     /// - The enable pin is used to tell the `LCD1602` that there
-    /// is data on the 4 data pins and that it should read them in.
+    ///   is data on the 4 data pins and that it should read them in.
     ///
     /// This mode operates differently than 8 bit mode by using 4 less
     /// pins for data, which is nice on devices with less I/O although
@@ -125,6 +227,7 @@ impl<RS: OutputPin, EN: OutputPin, D4: OutputPin, D5: OutputPin, D6: OutputPin,
     /// broken up into it's upper and lower nibbles (4 bits) before
     /// being sent over the data bus
     ///
+    #[allow(clippy::type_complexity)]
     pub fn new_4bit<D: DelayNs>(
         rs: RS,
         en: EN,
@@ -133,16 +236,207 @@ impl<RS: OutputPin, EN: OutputPin, D4: OutputPin, D5: OutputPin, D6: OutputPin,
         d6: D6,
         d7: D7,
         delay: &mut D,
-    ) -> Result<LCD1602<FourBitBus<RS, EN, D4, D5, D6, D7>>> {
-        let mut hd = LCD1602 {
-            bus: FourBitBus::from_pins(rs, en, d4, d5, d6, d7),
-            entry_mode: EntryMode::default(),
-            display_mode: DisplayMode::default(),
-        };
+    ) -> Result<LCD1602<FourBitBus<RS, EN, D4, D5, D6, D7>>, ErrorKind> {
+        let bus = FourBitBus::from_pins(rs, en, d4, d5, d6, d7);
+        let mut hd = LCD1602::from_parts(bus, Geometry::default());
 
         hd.init_4bit(delay)?;
 
-        return Ok(hd);
+        Ok(hd)
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN1: OutputPin,
+        EN2: OutputPin,
+        D0: OutputPin,
+        D1: OutputPin,
+        D2: OutputPin,
+        D3: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+    > LCD1602<EightBitDualBus<RS, EN1, EN2, D0, D1, D2, D3, D4, D5, D6, D7>>
+{
+    /// Create an instance of a `LCD1602` for a 40x4 display from 8 data pins,
+    /// a register select pin, the two enable pins and a struct implementing the
+    /// delay trait.
+    /// - 40x4 displays are made of two controllers that share the register
+    ///   select and data pins. `en1` is the enable pin of the upper two rows,
+    ///   `en2` of the lower two.
+    /// - Both controllers are set up, and the display uses the
+    ///   [40x4 geometry](Geometry::LCD40X4). Rows are sent to the controller
+    ///   that shows them, and only that controller shows the cursor.
+    ///
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    pub fn new_8bit_dual<D: DelayNs>(
+        rs: RS,
+        en1: EN1,
+        en2: EN2,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        delay: &mut D,
+    ) -> Result<LCD1602<EightBitDualBus<RS, EN1, EN2, D0, D1, D2, D3, D4, D5, D6, D7>>, ErrorKind>
+    {
+        let bus = EightBitDualBus::from_pins(rs, en1, en2, d0, d1, d2, d3, d4, d5, d6, d7);
+        let mut hd = LCD1602::from_parts(bus, Geometry::LCD40X4);
+
+        hd.init_8bit(delay)?;
+        hd.init_second_controller(delay)?;
+
+        Ok(hd)
+    }
+}
+
+impl<
+        RS: OutputPin,
+        EN1: OutputPin,
+        EN2: OutputPin,
+        D4: OutputPin,
+        D5: OutputPin,
+        D6: OutputPin,
+        D7: OutputPin,
+    > LCD1602<FourBitDualBus<RS, EN1, EN2, D4, D5, D6, D7>>
+{
+    /// Create an instance of a `LCD1602` for a 40x4 display from 4 data pins,
+    /// a register select pin, the two enable pins and a struct implementing the
+    /// delay trait. See [new_8bit_dual](#method.new_8bit_dual).
+    ///
+    /// ```rust,ignore
+    /// let mut lcd = LCD1602::new_4bit_dual(rs, en1, en2, d4, d5, d6, d7, &mut delay)?;
+    /// lcd.set_cursor(0, 3, &mut delay)?;
+    /// lcd.write_str("Bottom row", &mut delay)?;
+    /// ```
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    pub fn new_4bit_dual<D: DelayNs>(
+        rs: RS,
+        en1: EN1,
+        en2: EN2,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        delay: &mut D,
+    ) -> Result<LCD1602<FourBitDualBus<RS, EN1, EN2, D4, D5, D6, D7>>, ErrorKind> {
+        let bus = FourBitDualBus::from_pins(rs, en1, en2, d4, d5, d6, d7);
+        let mut hd = LCD1602::from_parts(bus, Geometry::LCD40X4);
+
+        hd.init_4bit(delay)?;
+        hd.init_second_controller(delay)?;
+
+        Ok(hd)
+    }
+}
+
+impl<
+        RS: OutputPin,
+        RW: OutputPin,
+        EN: OutputPin,
+        D0: InputPin + OutputPin,
+        D1: InputPin + OutputPin,
+        D2: InputPin + OutputPin,
+        D3: InputPin + OutputPin,
+        D4: InputPin + OutputPin,
+        D5: InputPin + OutputPin,
+        D6: InputPin + OutputPin,
+        D7: InputPin + OutputPin,
+    > LCD1602<EightBitRwBus<RS, RW, EN, D0, D1, D2, D3, D4, D5, D6, D7>>
+{
+    /// Create an instance of a `LCD1602` from 8 data pins, a register select
+    /// pin, a read/write pin, an enable pin and a struct implementing the delay trait.
+    /// - The delay instance is used for the enable pulses and while polling
+    ///   the busy flag.
+    /// - The eight db0..db7 pins are used to send and recieve with
+    ///   the `LCD1602`. They are read back, so they need to implement
+    ///   `InputPin` as well as `OutputPin`.
+    /// - The register select pin is used to tell the `LCD1602`
+    ///   if incoming data is a command or data.
+    /// - The read/write pin is used to tell the `LCD1602` if it should
+    ///   read from or drive the data pins.
+    /// - The enable pin is used to tell the `LCD1602` that there
+    ///   is data on the 8 data pins and that it should read them in.
+    ///
+    /// Instead of sleeping a fixed time after every command, this mode
+    /// polls the busy flag of the `LCD1602` and carries on as soon as
+    /// the command has been processed.
+    ///
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    pub fn new_8bit_rw<D: DelayNs>(
+        rs: RS,
+        rw: RW,
+        en: EN,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        delay: &mut D,
+    ) -> Result<LCD1602<EightBitRwBus<RS, RW, EN, D0, D1, D2, D3, D4, D5, D6, D7>>, ErrorKind> {
+        let bus = EightBitRwBus::from_pins(rs, rw, en, d0, d1, d2, d3, d4, d5, d6, d7);
+        let mut hd = LCD1602::from_parts(bus, Geometry::default());
+
+        hd.init_8bit(delay)?;
+
+        Ok(hd)
+    }
+}
+
+impl<
+        RS: OutputPin,
+        RW: OutputPin,
+        EN: OutputPin,
+        D4: InputPin + OutputPin,
+        D5: InputPin + OutputPin,
+        D6: InputPin + OutputPin,
+        D7: InputPin + OutputPin,
+    > LCD1602<FourBitRwBus<RS, RW, EN, D4, D5, D6, D7>>
+{
+    /// Create an instance of a `LCD1602` from 4 data pins, a register select
+    /// pin, a read/write pin, an enable pin and a struct implementing the delay trait.
+    /// - The delay instance is used for the enable pulses and while polling
+    ///   the busy flag.
+    /// - The four db4..db7 pins are used to send and recieve with
+    ///   the `LCD1602`. They are read back, so they need to implement
+    ///   `InputPin` as well as `OutputPin`.
+    /// - The register select pin is used to tell the `LCD1602`
+    ///   if incoming data is a command or data.
+    /// - The read/write pin is used to tell the `LCD1602` if it should
+    ///   read from or drive the data pins.
+    /// - The enable pin is used to tell the `LCD1602` that there
+    ///   is data on the 4 data pins and that it should read them in.
+    ///
+    /// Instead of sleeping a fixed time after every command, this mode
+    /// polls the busy flag of the `LCD1602` and carries on as soon as
+    /// the command has been processed.
+    ///
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    pub fn new_4bit_rw<D: DelayNs>(
+        rs: RS,
+        rw: RW,
+        en: EN,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        delay: &mut D,
+    ) -> Result<LCD1602<FourBitRwBus<RS, RW, EN, D4, D5, D6, D7>>, ErrorKind> {
+        let bus = FourBitRwBus::from_pins(rs, rw, en, d4, d5, d6, d7);
+        let mut hd = LCD1602::from_parts(bus, Geometry::default());
+
+        hd.init_4bit(delay)?;
+
+        Ok(hd)
     }
 }
 
@@ -150,9 +444,9 @@ impl<I2C: I2c> LCD1602<I2CBus<I2C>> {
     /// Create an instance of a `LCD1602` from an i2c write peripheral,
     /// the `LCD1602` I2C address and a struct implementing the delay trait.
     /// - The delay instance is used to sleep between commands to
-    /// ensure the `LCD1602` has enough time to process commands.
+    ///   ensure the `LCD1602` has enough time to process commands.
     /// - The i2c peripheral is used to send data to the `LCD1602` and to set
-    /// its register select and enable pins.
+    ///   its register select and enable pins.
     ///
     /// This mode operates on an I2C bus, using an I2C to parallel port expander
     ///
@@ -160,16 +454,211 @@ impl<I2C: I2c> LCD1602<I2CBus<I2C>> {
         i2c_bus: I2C,
         address: u8,
         delay: &mut D,
-    ) -> Result<LCD1602<I2CBus<I2C>>> {
-        let mut hd = LCD1602 {
-            bus: I2CBus::new(i2c_bus, address),
-            entry_mode: EntryMode::default(),
-            display_mode: DisplayMode::default(),
-        };
+    ) -> Result<LCD1602<I2CBus<I2C>>, I2C::Error> {
+        let bus = I2CBus::new(i2c_bus, address);
+        let mut hd = LCD1602::from_parts(bus, Geometry::default());
+
+        hd.init_4bit(delay)?;
+
+        Ok(hd)
+    }
+
+    /// Create an instance of a `LCD1602` from an i2c write peripheral,
+    /// the `LCD1602` I2C address, the pin mapping of the backpack and a
+    /// struct implementing the delay trait.
+    /// - The pin mapping tells the driver which expander pin each line of the
+    ///   `LCD1602` is wired to. See [PinMap] for presets of common boards.
+    ///   A mapping that uses a pin past P7 is rejected with [Error::InvalidPinMap].
+    ///
+    /// Otherwise this works the same as [new_i2c](#method.new_i2c).
+    ///
+    /// ```rust,ignore
+    /// let lcd = LCD1602::new_i2c_with_pin_map(i2c, 0x20, PinMap::MJKDZ, &mut delay)?;
+    /// ```
+    pub fn new_i2c_with_pin_map<D: DelayNs>(
+        i2c_bus: I2C,
+        address: u8,
+        pin_map: PinMap,
+        delay: &mut D,
+    ) -> Result<LCD1602<I2CBus<I2C>>, I2C::Error> {
+        let bus = I2CBus::with_pin_map(i2c_bus, address, pin_map)?;
+        let mut hd = LCD1602::from_parts(bus, Geometry::default());
+
+        hd.init_4bit(delay)?;
+
+        Ok(hd)
+    }
+}
+
+impl<I2C: I2c> LCD1602<Mcp230xxBus<I2C>> {
+    /// Create an instance of a `LCD1602` on an MCP23008 port expander wired
+    /// like the Adafruit I2C/SPI backpack, from an i2c peripheral, the
+    /// expander I2C address and a struct implementing the delay trait.
+    /// - The delay instance is used to sleep between commands to
+    ///   ensure the `LCD1602` has enough time to process commands.
+    /// - The i2c peripheral is used to program the expander registers and
+    ///   to drive the data, register select and enable pins through it.
+    ///
+    /// ```rust,ignore
+    /// let lcd = LCD1602::new_mcp23008(i2c, 0x20, &mut delay)?;
+    /// ```
+    pub fn new_mcp23008<D: DelayNs>(
+        i2c_bus: I2C,
+        address: u8,
+        delay: &mut D,
+    ) -> Result<LCD1602<Mcp230xxBus<I2C>>, I2C::Error> {
+        Self::new_mcp230xx(i2c_bus, address, Mcp230xx::Mcp23008, PinMap::ADAFRUIT, delay)
+    }
+
+    /// Create an instance of a `LCD1602` on an MCP23017 port expander wired
+    /// like the Adafruit RGB LCD shield. See [new_mcp23008](#method.new_mcp23008)
+    /// for the arguments.
+    ///
+    /// Pins not used by the `LCD1602`, such as the buttons of the shield,
+    /// are left as inputs.
+    pub fn new_mcp23017<D: DelayNs>(
+        i2c_bus: I2C,
+        address: u8,
+        delay: &mut D,
+    ) -> Result<LCD1602<Mcp230xxBus<I2C>>, I2C::Error> {
+        Self::new_mcp230xx(
+            i2c_bus,
+            address,
+            Mcp230xx::Mcp23017,
+            PinMap::ADAFRUIT_RGB_SHIELD,
+            delay,
+        )
+    }
+
+    /// Create an instance of a `LCD1602` on an MCP23008 or MCP23017 port
+    /// expander with a custom pin mapping. See [new_mcp23008](#method.new_mcp23008)
+    /// for the other arguments.
+    /// - The pin mapping tells the driver which expander pin each line of the
+    ///   `LCD1602` is wired to, counting port B of the MCP23017 as pins 8 to 15.
+    ///   A mapping that uses a pin the expander does not have is rejected with
+    ///   [Error::InvalidPinMap].
+    pub fn new_mcp230xx<D: DelayNs>(
+        i2c_bus: I2C,
+        address: u8,
+        variant: Mcp230xx,
+        pin_map: PinMap,
+        delay: &mut D,
+    ) -> Result<LCD1602<Mcp230xxBus<I2C>>, I2C::Error> {
+        let bus = Mcp230xxBus::new(i2c_bus, address, variant, pin_map)?;
+        let mut hd = LCD1602::from_parts(bus, Geometry::default());
+
+        hd.bus.configure()?;
+        hd.init_4bit(delay)?;
+
+        Ok(hd)
+    }
+}
+
+impl<S: ShiftOut> LCD1602<ShiftRegisterBus<S>> {
+    /// Create an instance of a `LCD1602` behind a 74HC595 shift register, from
+    /// anything implementing [ShiftOut], the pin mapping of the shift register
+    /// outputs and a struct implementing the delay trait.
+    /// - The delay instance is used to sleep between commands to
+    ///   ensure the `LCD1602` has enough time to process commands.
+    /// - The pin mapping tells the driver which output of the shift register
+    ///   each line of the `LCD1602` is wired to. A mapping that uses a pin past
+    ///   7 is rejected with [Error::InvalidPinMap].
+    ///
+    /// ```rust,ignore
+    /// let register = SpiShiftRegister::new(spi);
+    /// let lcd = LCD1602::new_shift_register(register, PinMap::ADAFRUIT_SPI, &mut delay)?;
+    /// ```
+    pub fn new_shift_register<D: DelayNs>(
+        register: S,
+        pin_map: PinMap,
+        delay: &mut D,
+    ) -> Result<LCD1602<ShiftRegisterBus<S>>, S::Error> {
+        let bus = ShiftRegisterBus::new(register, pin_map)?;
+        let mut hd = LCD1602::from_parts(bus, Geometry::default());
 
         hd.init_4bit(delay)?;
 
-        return Ok(hd);
+        Ok(hd)
+    }
+}
+
+impl<SPI: SpiDevice> LCD1602<ShiftRegisterBus<SpiShiftRegister<SPI>>> {
+    /// Create an instance of a `LCD1602` on the 74HC595 of an Adafruit I2C/SPI
+    /// backpack in SPI mode, from an SPI device and a struct implementing the
+    /// delay trait.
+    /// - The SPI device should use the latch pin of the backpack as its
+    ///   chip select.
+    ///
+    /// This mode is a lot faster than going through the PCF8574 I2C expander
+    ///
+    pub fn new_spi_595<D: DelayNs>(
+        spi: SPI,
+        delay: &mut D,
+    ) -> Result<LCD1602<ShiftRegisterBus<SpiShiftRegister<SPI>>>, SPI::Error> {
+        Self::new_shift_register(SpiShiftRegister::new(spi), PinMap::ADAFRUIT_SPI, delay)
+    }
+}
+
+impl<DATA: OutputPin, CLK: OutputPin, LATCH: OutputPin>
+    LCD1602<ShiftRegisterBus<GpioShiftRegister<DATA, CLK, LATCH>>>
+{
+    /// Create an instance of a `LCD1602` on the 74HC595 of an Adafruit I2C/SPI
+    /// backpack in SPI mode, bit-banging the data, clock and latch pins
+    /// instead of using an SPI peripheral.
+    ///
+    #[allow(clippy::type_complexity)]
+    pub fn new_gpio_595<D: DelayNs>(
+        data: DATA,
+        clock: CLK,
+        latch: LATCH,
+        delay: &mut D,
+    ) -> Result<LCD1602<ShiftRegisterBus<GpioShiftRegister<DATA, CLK, LATCH>>>, ErrorKind> {
+        let register = GpioShiftRegister::new(data, clock, latch);
+
+        Self::new_shift_register(register, PinMap::ADAFRUIT_SPI, delay)
+    }
+}
+
+impl<B: Backlight> LCD1602<B> {
+    /// Turn the backlight on or off. This takes effect immediately and is
+    /// kept for all later writes.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_backlight(false)?;
+    /// ```
+    pub fn set_backlight(&mut self, on: bool) -> Result<(), B::Error> {
+        self.bus.set_backlight(on)
+    }
+
+    /// Returns whether the backlight is on
+    pub fn backlight(&self) -> bool {
+        self.bus.backlight()
+    }
+
+    /// Blink the backlight `times` times to draw attention to the display,
+    /// blocking until done. Each blink takes `period_ms` milliseconds, half of
+    /// it with the backlight toggled. The backlight is left as it was.
+    ///
+    /// ```rust,ignore
+    /// // Three blinks, half a second each
+    /// lcd.blink_backlight(3, 500, &mut delay)?;
+    /// ```
+    pub fn blink_backlight<D: DelayNs>(
+        &mut self,
+        times: u8,
+        period_ms: u32,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        let on = self.bus.backlight();
+
+        for _ in 0..times {
+            self.bus.set_backlight(!on)?;
+            delay.delay_ms(period_ms / 2);
+            self.bus.set_backlight(on)?;
+            delay.delay_ms(period_ms - period_ms / 2);
+        }
+
+        Ok(())
     }
 }
 
@@ -177,13 +666,51 @@ impl<B> LCD1602<B>
 where
     B: DataBus,
 {
+    // A driver for a display that has not been set up yet, with every setting
+    // at its default
+    fn from_parts(bus: B, geometry: Geometry) -> LCD1602<B> {
+        LCD1602 {
+            bus,
+            entry_mode: EntryMode::default(),
+            display_mode: DisplayMode::default(),
+            geometry,
+            address: 0,
+            text_mode: TextMode::default(),
+            wrap_pending: None,
+            rom: None,
+            fallback: b'?',
+            glyph_sets: &[],
+            glyph_cache: GlyphCache::default(),
+            decimal_separator: b'.',
+            ddram: DdramShadow::default(),
+            function_set: command::FUNCTION_SET_4BIT,
+        }
+    }
+
+    /// Moves the delay provider into the driver, so its methods no longer take one.
+    ///
+    /// ```rust,ignore
+    /// let mut lcd = LCD1602::new_4bit(rs, en, d4, d5, d6, d7, &mut delay)?.with_delay(delay);
+    /// lcd.write_str("Hello, world!")?;
+    /// ```
+    pub fn with_delay<D: DelayNs>(self, delay: D) -> LCD1602WithDelay<B, D> {
+        LCD1602WithDelay::new(self, delay)
+    }
+
+    /// Gives back the bus the display is connected through.
+    pub fn release(self) -> B {
+        self.bus
+    }
+
     /// Unshifts the display and sets the cursor position to 0
     ///
     /// ```rust,ignore
     /// lcd.reset();
     /// ```
-    pub fn reset<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
-        self.write_command(0b0000_0010, delay)?;
+    pub fn reset<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), B::Error> {
+        self.write_command(command::RETURN_HOME, delay)?;
+        self.track_address(0, delay)?;
+        self.wrap_pending = None;
 
         Ok(())
     }
@@ -197,12 +724,10 @@ where
         &mut self,
         display_mode: DisplayMode,
         delay: &mut D,
-    ) -> Result<()> {
+    ) -> Result<(), B::Error> {
         self.display_mode = display_mode;
 
-        let cmd_byte = self.display_mode.as_byte();
-
-        self.write_command(cmd_byte, delay)?;
+        self.write_display_control(delay)?;
 
         Ok(())
     }
@@ -212,8 +737,12 @@ where
     /// ```rust,ignore
     /// lcd.clear();
     /// ```
-    pub fn clear<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
-        self.write_command(0b0000_0001, delay)?;
+    pub fn clear<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), B::Error> {
+        self.write_command(command::CLEAR_DISPLAY, delay)?;
+        self.track_address(0, delay)?;
+        self.wrap_pending = None;
+        self.glyph_cache.cleared();
+        self.ddram.cleared();
 
         Ok(())
     }
@@ -228,8 +757,8 @@ where
         &mut self,
         enabled: ShiftMode,
         delay: &mut D,
-    ) -> Result<()> {
-        self.entry_mode.display_shift = enabled.into();
+    ) -> Result<(), B::Error> {
+        self.entry_mode.display_shift = enabled;
 
         let cmd = self.entry_mode.as_byte();
 
@@ -243,12 +772,10 @@ where
         &mut self,
         visibility: Cursor,
         delay: &mut D,
-    ) -> Result<()> {
+    ) -> Result<(), B::Error> {
         self.display_mode.cursor_visibility = visibility;
 
-        let cmd = self.display_mode.as_byte();
-
-        self.write_command(cmd, delay)?;
+        self.write_display_control(delay)?;
 
         Ok(())
     }
@@ -258,12 +785,10 @@ where
         &mut self,
         display: Display,
         delay: &mut D,
-    ) -> Result<()> {
+    ) -> Result<(), B::Error> {
         self.display_mode.display = display;
 
-        let cmd = self.display_mode.as_byte();
-
-        self.write_command(cmd, delay)?;
+        self.write_display_control(delay)?;
 
         Ok(())
     }
@@ -273,12 +798,10 @@ where
         &mut self,
         blink: CursorBlink,
         delay: &mut D,
-    ) -> Result<()> {
+    ) -> Result<(), B::Error> {
         self.display_mode.cursor_blink = blink;
 
-        let cmd = self.display_mode.as_byte();
-
-        self.write_command(cmd, delay)?;
+        self.write_display_control(delay)?;
 
         Ok(())
     }
@@ -296,7 +819,7 @@ where
         &mut self,
         mode: CursorMode,
         delay: &mut D,
-    ) -> Result<()> {
+    ) -> Result<(), B::Error> {
         self.entry_mode.move_direction = mode;
 
         let cmd = self.entry_mode.as_byte();
@@ -306,24 +829,80 @@ where
         Ok(())
     }
 
-    /// Set the cursor position
+    /// Set the size and DDRAM layout of the attached panel. Displays are
+    /// assumed to be 16x2 until this is called.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_geometry(Geometry::LCD20X4);
+    /// ```
+    pub fn set_geometry(&mut self, geometry: Geometry) {
+        self.geometry = geometry;
+    }
+
+    /// Returns the size and DDRAM layout of the attached panel
+    pub fn geometry(&self) -> Geometry {
+        self.geometry
+    }
+
+    /// Switch between the 5x8 and the 5x10 character font. Glyphs for the 5x10
+    /// font are stored with [create_char_5x10](#method.create_char_5x10).
+    ///
+    /// ```rust,ignore
+    /// lcd.set_geometry(Geometry::LCD16X1_TYPE2);
+    /// lcd.set_font(Font::Dots5x10, &mut delay)?;
+    /// ```
+    pub fn set_font<D: DelayNs>(&mut self, font: Font, delay: &mut D) -> Result<(), B::Error> {
+        let lines_and_font = match font {
+            Font::Dots5x8 => command::TWO_LINES_5X8,
+            Font::Dots5x10 => command::ONE_LINE_5X10,
+        };
+        self.function_set = self.function_set & !command::LINES_AND_FONT | lines_and_font;
+
+        self.write_command(self.function_set, delay)
+    }
+
+    /// Set the cursor position to a raw DDRAM address. Use
+    /// [set_cursor](#method.set_cursor) to address cells by column and row.
+    ///
+    /// Returns an error if the address does not fit in the 7 bit address counter.
+    /// With a [40x4 geometry](Geometry::LCD40X4), bit 7 selects the second
+    /// controller.
     ///
     /// ```rust,ignore
-    /// // Move to line 2
-    /// lcd.set_cursor_pos(40)
+    /// // Move to the start of line 2
+    /// lcd.set_cursor_pos(0x40)
     /// ```
     pub fn set_cursor_pos<D: DelayNs>(
         &mut self,
         position: u8,
         delay: &mut D,
-    ) -> Result<()> {
-        let lower_7_bits = 0b0111_1111 & position;
-
-        self.write_command(0b1000_0000 | lower_7_bits, delay)?;
+    ) -> Result<(), B::Error> {
+        self.set_address(position, delay)?;
+        self.wrap_pending = None;
 
         Ok(())
     }
 
+    /// Move the cursor to a column and row of the display, both counted from 0.
+    /// The DDRAM address is worked out from the display [geometry](#method.set_geometry).
+    ///
+    /// Returns an error if the cell is not on the display.
+    ///
+    /// ```rust,ignore
+    /// // Move to the first column of the second row
+    /// lcd.set_cursor(0, 1, &mut delay)?;
+    /// ```
+    pub fn set_cursor<D: DelayNs>(
+        &mut self,
+        column: u8,
+        row: u8,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        let position = self.geometry.address(column, row).ok_or(Error::OutOfBounds)?;
+
+        self.set_cursor_pos(position, delay)
+    }
+
     /// Shift just the cursor to the left or the right
     ///
     /// ```rust,ignore
@@ -334,13 +913,10 @@ where
         &mut self,
         dir: Direction,
         delay: &mut D,
-    ) -> Result<()> {
-        let bits = match dir {
-            Direction::Left => 0b0000_0000,
-            Direction::Right => 0b0000_0100,
-        };
-
-        self.write_command(0b0001_0000 | bits | bits, delay)?;
+    ) -> Result<(), B::Error> {
+        self.write_command_to(self.controller(), command::cursor_shift(&dir), delay)?;
+        self.address = command::next_address(self.address, matches!(dir, Direction::Right));
+        self.wrap_pending = None;
 
         Ok(())
     }
@@ -355,148 +931,286 @@ where
         &mut self,
         dir: Direction,
         delay: &mut D,
-    ) -> Result<()> {
-        let bits = match dir {
-            Direction::Left => 0b0000_0000,
-            Direction::Right => 0b0000_0100,
-        };
-
-        self.write_command(0b0001_1000 | bits, delay)?;
+    ) -> Result<(), B::Error> {
+        self.write_command(command::display_shift(&dir), delay)?;
 
         Ok(())
     }
 
-    /// Write a single character to the `LCD1602`. This `char` just gets downcast to a `u8`
-    /// internally, so make sure that whatever character you're printing fits inside that range, or
-    /// you can just use [write_byte](#method.write_byte) to have the compiler check for you.
+    /// Write a single character to the `LCD1602`. Unless a [character ROM](#method.set_character_rom)
+    /// is set, this `char` just gets downcast to a `u8` internally, so make sure that whatever
+    /// character you're printing fits inside that range, or you can just use
+    /// [write_byte](#method.write_byte) to have the compiler check for you.
     /// See the documentation on that function for more details about compatibility.
     ///
     /// ```rust,ignore
     /// lcd.write_char('A', &mut delay)?; // prints 'A'
     /// ```
+    ///
+    /// Control characters are handled as described for the [text mode](#method.set_text_mode).
     pub fn write_char<D: DelayNs>(
         &mut self,
         data: char,
         delay: &mut D,
-    ) -> Result<()> {
-        self.write_byte(data as u8, delay)
+    ) -> Result<(), B::Error> {
+        let mode = self.text_mode;
+
+        self.write_text(data, mode, delay)
+    }
+
+    /// Returns a handle that implements [core::fmt::Write], so the display can be
+    /// used with `write!`. Text is written in the current [text mode](#method.set_text_mode),
+    /// except that [TextMode::Raw] is treated as [TextMode::Wrap]: text wraps onto
+    /// the next row at the end of each row of the display [geometry](#method.set_geometry),
+    /// and from the last row back to the first.
+    ///
+    /// ```rust,ignore
+    /// write!(lcd.writer(&mut delay), "T={:.1}C", temperature)?;
+    /// ```
+    pub fn writer<'a, D: DelayNs>(&'a mut self, delay: &'a mut D) -> Writer<'a, B, D> {
+        Writer::new(self, delay)
+    }
+
+    /// Sends the cells of a [FrameBuffer] that differ from what the display is
+    /// showing. The cursor is only moved where the changed cells are not next to
+    /// each other, so a mostly unchanged screen costs a few bytes on the bus.
+    ///
+    /// Returns an error if the buffer is larger than the display
+    /// [geometry](#method.set_geometry). The cursor is left after the last
+    /// changed cell.
+    ///
+    /// ```rust,ignore
+    /// let mut frame = FrameBuffer::<16, 2>::new();
+    /// frame.write_bytes(b"Hello, world!");
+    /// lcd.flush(&mut frame, &mut delay)?;
+    /// ```
+    pub fn flush<D: DelayNs, const COLS: usize, const ROWS: usize>(
+        &mut self,
+        frame: &mut FrameBuffer<COLS, ROWS>,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        if COLS > self.geometry.columns() as usize || ROWS > self.geometry.rows() as usize {
+            return Err(Error::OutOfBounds);
+        }
+
+        for row in 0..ROWS {
+            for column in 0..COLS {
+                let Some(data) = frame.pending(column, row) else {
+                    continue;
+                };
+
+                let address = self
+                    .geometry
+                    .address(column as u8, row as u8)
+                    .ok_or(Error::OutOfBounds)?;
+                if address != self.address {
+                    self.set_cursor_pos(address, delay)?;
+                }
+
+                self.write_byte(data, delay)?;
+                frame.mark_sent(column, row);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Point the address counter at a DDRAM address without touching the text
+    // mode state, for putting the cursor back after reads and CGRAM writes
+    fn set_address<D: DelayNs>(&mut self, position: u8, delay: &mut D) -> Result<(), B::Error> {
+        if position > command::MAX_DDRAM_ADDRESS && self.geometry.controllers() == 1 {
+            return Err(Error::OutOfBounds);
+        }
+
+        let cmd = command::SET_DDRAM_ADDRESS | (position & command::MAX_DDRAM_ADDRESS);
+        self.write_command_to(command::controller(position), cmd, delay)?;
+
+        self.track_address(position, delay)
+    }
+
+    // Take on a new address counter value. On a display with two controllers
+    // later writes go to the controller of that address, which also gets the
+    // cursor.
+    fn track_address<D: DelayNs>(&mut self, address: u8, delay: &mut D) -> Result<(), B::Error> {
+        let switched = command::controller(address) != self.controller();
+
+        self.address = address;
+        self.bus.select_controller(self.controller())?;
+
+        if switched {
+            self.write_display_control(delay)?;
+        }
+
+        Ok(())
     }
 
+    // Controller the address counter is on
+    fn controller(&self) -> Controller {
+        command::controller(self.address)
+    }
+
+    // Send the display control state, with the cursor off on the controller
+    // that is not being written to
+    fn write_display_control<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), B::Error> {
+        let cmd = self.display_mode.as_byte();
+
+        if self.geometry.controllers() == 1 {
+            return self.write_command(cmd, delay);
+        }
+
+        let (active, other) = match self.controller() {
+            Controller::Second => (Controller::Second, Controller::First),
+            _ => (Controller::First, Controller::Second),
+        };
+
+        self.write_command_to(other, cmd & !command::CURSOR_BITS, delay)?;
+        self.write_command_to(active, cmd, delay)
+    }
+
+    // Send a command to every controller of the display
     fn write_command<D: DelayNs>(
         &mut self,
         cmd: u8,
         delay: &mut D,
-    ) -> Result<()> {
+    ) -> Result<(), B::Error> {
+        self.write_command_to(Controller::Both, cmd, delay)
+    }
+
+    fn write_command_to<D: DelayNs>(
+        &mut self,
+        controller: Controller,
+        cmd: u8,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        self.bus.select_controller(controller)?;
         self.bus.write(cmd, false, delay)?;
+        self.wait_ready(command::execution_time_us(cmd), delay)?;
+
+        self.bus.select_controller(self.controller())
+    }
+
+    // Wait for the display to finish the last instruction, polling the busy flag
+    // if the bus can read it and sleeping for `fallback_us` otherwise
+    fn wait_ready<D: DelayNs>(&mut self, fallback_us: u32, delay: &mut D) -> Result<(), B::Error> {
+        if !self.bus.wait_ready(delay)? {
+            delay.delay_us(fallback_us);
+        }
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
         Ok(())
     }
 
-    fn init_4bit<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+    fn init_4bit<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), B::Error> {
         // Wait for the LCD to wakeup if it was off
         delay.delay_ms(15u32);
 
-        // Initialize Lcd in 4-bit mode
-        self.bus.write(0x33, false, delay)?;
+        // Initialize Lcd in 4-bit mode. The display still expects 8-bit
+        // transfers at this point, so these go out as single nibbles.
+        self.bus.write_nibble(command::WAKE_UP, delay)?;
 
         // Wait for the command to be processed
         delay.delay_ms(5u32);
 
-        // Sets 4-bit operation and enables 5x7 mode for chars
-        self.bus.write(0x32, false, delay)?;
+        self.bus.write_nibble(command::WAKE_UP, delay)?;
 
         // Wait for the command to be processed
         delay.delay_us(100);
 
-        self.bus.write(0x28, false, delay)?;
+        self.bus.write_nibble(command::WAKE_UP, delay)?;
 
         // Wait for the command to be processed
         delay.delay_us(100);
 
-        // Clear Display
-        self.bus.write(0x0E, false, delay)?;
+        // Sets 4-bit operation
+        self.bus.write_nibble(command::FOUR_BIT_INTERFACE, delay)?;
 
         // Wait for the command to be processed
         delay.delay_us(100);
 
-        // Move the cursor to beginning of first line
-        self.bus.write(0x01, false, delay)?;
+        // Two lines and 5x8 mode for chars
+        self.function_set = command::FUNCTION_SET_4BIT;
+        self.write_command(self.function_set, delay)?;
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
+        // Display on
+        self.write_command(command::INIT_DISPLAY_CONTROL, delay)?;
+
+        // Clear Display
+        self.write_command(command::CLEAR_DISPLAY, delay)?;
 
         // Set entry mode
-        self.bus.write(self.entry_mode.as_byte(), false, delay)?;
+        self.write_command(self.entry_mode.as_byte(), delay)?;
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
+        // Move the cursor to beginning of first line
+        self.write_command(command::SET_DDRAM_ADDRESS, delay)?;
 
-        self.bus.write(0x80, false, delay)?;
+        Ok(())
+    }
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
+    // The dual buses start out writing to both controllers, so both have been set
+    // up and show the cursor. Go on with the first one and hide the cursor on the
+    // second.
+    fn init_second_controller<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), B::Error> {
+        let cmd = command::INIT_DISPLAY_CONTROL & !command::CURSOR_BITS;
 
-        Ok(())
+        self.write_command_to(Controller::Second, cmd, delay)
     }
 
     // Follow the 8-bit setup procedure as specified in the LCD1602 datasheet
-    fn init_8bit<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+    fn init_8bit<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), B::Error> {
         // Wait for the LCD to wakeup if it was off
         delay.delay_ms(15u32);
 
         // Initialize Lcd in 8-bit mode
-        self.bus.write(0b0011_0000, false, delay)?;
+        self.bus.write(command::WAKE_UP, false, delay)?;
 
         // Wait for the command to be processed
         delay.delay_ms(5u32);
 
         // Sets 8-bit operation and enables 5x7 mode for chars
-        self.bus.write(0b0011_1000, false, delay)?;
+        self.function_set = command::FUNCTION_SET_8BIT;
+        self.write_command(self.function_set, delay)?;
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
-
-        self.bus.write(0b0000_1110, false, delay)?;
-
-        // Wait for the command to be processed
-        delay.delay_us(100);
+        // Display on
+        self.write_command(command::INIT_DISPLAY_CONTROL, delay)?;
 
         // Clear Display
-        self.bus.write(0b0000_0001, false, delay)?;
-
-        // Wait for the command to be processed
-        delay.delay_us(100);
+        self.write_command(command::CLEAR_DISPLAY, delay)?;
 
         // Move the cursor to beginning of first line
-        self.bus.write(0b000_0111, false, delay)?;
-
-        // Wait for the command to be processed
-        delay.delay_us(100);
+        self.write_command(0b000_0111, delay)?;
 
         // Set entry mode
-        self.bus.write(self.entry_mode.as_byte(), false, delay)?;
-
-        // Wait for the command to be processed
-        delay.delay_us(100);
+        self.write_command(self.entry_mode.as_byte(), delay)?;
 
         Ok(())
     }
 
-    /// Writes a string to the LCD1602. Internally, this just prints the string byte-by-byte, so
-    /// make sure the characters in the string fit in a normal `u8`. See the documentation on
-    /// [write_byte](#method.write_byte) for more details on compatibility.
+    /// Writes a string to the LCD1602. Unless a [character ROM](#method.set_character_rom) is
+    /// set, this just prints the string byte-by-byte, so make sure the characters in the string
+    /// fit in a normal `u8`. See the documentation on [write_byte](#method.write_byte) for more
+    /// details on compatibility.
     ///
     /// ```rust,ignore
     /// lcd.write_str("Hello, World!", &mut delay)?;
     /// ```
+    ///
+    /// Outside of [TextMode::Raw], newlines, carriage returns, tabs and backspaces
+    /// move the cursor and text wraps at the end of each row, see
+    /// [set_text_mode](#method.set_text_mode).
     pub fn write_str<D: DelayNs>(
         &mut self,
         string: &str,
         delay: &mut D,
-    ) -> Result<()> {
-        self.write_bytes(string.as_bytes(), delay)
+    ) -> Result<(), B::Error> {
+        let mode = self.text_mode;
+        if mode == TextMode::Raw && self.rom.is_none() && self.glyph_sets.is_empty() {
+            return self.write_bytes(string.as_bytes(), delay);
+        }
+
+        for c in string.chars() {
+            self.write_text(c, mode, delay)?;
+        }
+
+        Ok(())
     }
 
     /// Writes a sequence of bytes to the LCD1602. See the documentation on the
@@ -509,7 +1223,7 @@ where
         &mut self,
         string: &[u8],
         delay: &mut D,
-    ) -> Result<()> {
+    ) -> Result<(), B::Error> {
         for &b in string {
             self.write_byte(b, delay)?;
         }
@@ -534,15 +1248,207 @@ where
         &mut self,
         data: u8,
         delay: &mut D,
-    ) -> Result<()> {
-        self.bus.write(data, true, delay)?;
+    ) -> Result<(), B::Error> {
+        self.write_data(data, delay)?;
+        self.glyph_cache.written(self.address, data);
+        self.ddram.written(self.address, data);
 
-        // Wait for the command to be processed
-        delay.delay_us(100);
+        let forward = matches!(self.entry_mode.move_direction, CursorMode::Increment);
+        self.address = command::next_address(self.address, forward);
+        self.wrap_pending = None;
 
         Ok(())
     }
 
+    /// Stores a custom 5x8 character in one of the eight CGRAM slots. Each of the
+    /// eight bytes is one row of the glyph, top to bottom, using the lower five bits
+    /// with bit 4 as the leftmost pixel. Once stored, the glyph is printed by writing
+    /// the slot number as a byte.
+    ///
+    /// The cursor is put back where it was once the glyph has been written.
+    ///
+    /// ```rust,ignore
+    /// const DEGREE: [u8; 8] = [
+    ///     0b00110, 0b01001, 0b01001, 0b00110, 0b00000, 0b00000, 0b00000, 0b00000,
+    /// ];
+    /// lcd.create_char(0, &DEGREE, &mut delay)?;
+    /// lcd.write_byte(0, &mut delay)?; // prints °
+    /// ```
+    pub fn create_char<D: DelayNs>(
+        &mut self,
+        slot: u8,
+        glyph: &[u8; 8],
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        if slot > 7 {
+            return Err(Error::InvalidCustomCharSlot);
+        }
+
+        self.glyph_cache.reserve(slot);
+
+        self.write_cgram(slot << 3, glyph, delay)
+    }
+
+    /// Stores a custom 5x10 character in one of the four CGRAM slots of the
+    /// [5x10 font](Font::Dots5x10). Each of the ten bytes is one row of the glyph,
+    /// top to bottom, in the layout [create_char](#method.create_char) takes. A
+    /// 5x10 slot takes up two 5x8 slots, and the rows past the tenth are left
+    /// blank. Once stored, the glyph is printed by writing `slot << 1` as a byte.
+    ///
+    /// The cursor is put back where it was once the glyph has been written.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_font(Font::Dots5x10, &mut delay)?;
+    /// lcd.create_char_5x10(1, &glyph, &mut delay)?;
+    /// lcd.write_byte(1 << 1, &mut delay)?;
+    /// ```
+    pub fn create_char_5x10<D: DelayNs>(
+        &mut self,
+        slot: u8,
+        glyph: &[u8; 10],
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        if slot > 3 {
+            return Err(Error::InvalidCustomCharSlot);
+        }
+
+        let mut rows = [0u8; 16];
+        rows[..10].copy_from_slice(glyph);
+
+        self.glyph_cache.reserve(slot << 1);
+        self.glyph_cache.reserve((slot << 1) + 1);
+
+        self.write_cgram(slot << 4, &rows, delay)
+    }
+
+    /// Hands a slot stored with [create_char](#method.create_char) back to the
+    /// [glyph sets](#method.set_glyph_sets), which load their glyphs into it once it
+    /// is no longer shown on the display. Until then the custom character stays
+    /// in the slot.
+    ///
+    /// Returns an error if the slot is not in CGRAM.
+    pub fn release_char(&mut self, slot: u8) -> Result<(), B::Error> {
+        if slot > 7 {
+            return Err(Error::InvalidCustomCharSlot);
+        }
+
+        self.glyph_cache.release(slot);
+
+        Ok(())
+    }
+
+    /// Reads the glyph stored in one of the eight CGRAM slots back from the
+    /// `LCD1602`, in the same row layout [create_char](#method.create_char) takes.
+    ///
+    /// Only buses that can read from the display support this, for any other
+    /// bus an error is returned. The cursor is put back where it was afterwards.
+    ///
+    /// ```rust,ignore
+    /// let glyph = lcd.read_cgram(0, &mut delay)?;
+    /// ```
+    pub fn read_cgram<D: DelayNs>(
+        &mut self,
+        slot: u8,
+        delay: &mut D,
+    ) -> Result<[u8; 8], B::Error> {
+        if slot > 7 {
+            return Err(Error::InvalidCustomCharSlot);
+        }
+
+        self.write_command(command::SET_CGRAM_ADDRESS | (slot << 3), delay)?;
+
+        let mut glyph = [0u8; 8];
+        let result = glyph.iter_mut().try_for_each(|row| {
+            *row = self.read_data(delay)? & command::GLYPH_ROW_MASK;
+            Ok(())
+        });
+
+        let address = self.address;
+        self.set_address(address, delay)?;
+        result?;
+
+        Ok(glyph)
+    }
+
+    /// Reads the character code stored at a DDRAM address back from the `LCD1602`.
+    ///
+    /// Only buses that can read from the display support this, for any other
+    /// bus an error is returned. The cursor is put back where it was afterwards.
+    ///
+    /// ```rust,ignore
+    /// lcd.write_str("Hello", &mut delay)?;
+    /// assert_eq!(lcd.read_char_at(0x00, &mut delay)?, b'H');
+    /// ```
+    pub fn read_char_at<D: DelayNs>(
+        &mut self,
+        position: u8,
+        delay: &mut D,
+    ) -> Result<u8, B::Error> {
+        let address = self.address;
+
+        self.set_address(position, delay)?;
+        let result = self.read_data(delay);
+        self.set_address(address, delay)?;
+
+        result
+    }
+
+    /// Reads the address counter of the `LCD1602`, which is the DDRAM address
+    /// the next character will be written to.
+    ///
+    /// Only buses that can read from the display support this, for any other
+    /// bus an error is returned.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_cursor(3, 1, &mut delay)?;
+    /// assert_eq!(lcd.cursor_address(&mut delay)?, 0x43);
+    /// ```
+    pub fn cursor_address<D: DelayNs>(&mut self, delay: &mut D) -> Result<u8, B::Error> {
+        let status = self.bus.read(false, delay)?;
+
+        Ok(status & command::MAX_DDRAM_ADDRESS | self.address & !command::MAX_DDRAM_ADDRESS)
+    }
+
+    fn read_data<D: DelayNs>(&mut self, delay: &mut D) -> Result<u8, B::Error> {
+        let data = self.bus.read(true, delay)?;
+
+        // Reading moves the address counter, which takes as long as a write
+        self.wait_ready(100, delay)?;
+
+        Ok(data)
+    }
+
+    // Write glyph rows starting at the given CGRAM address and then point the
+    // address counter back at the DDRAM position we came from
+    fn write_cgram<D: DelayNs>(
+        &mut self,
+        cgram_address: u8,
+        rows: &[u8],
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        self.write_command(command::SET_CGRAM_ADDRESS | (cgram_address & 0b0011_1111), delay)?;
+
+        // Both controllers of a 40x4 display need the glyph
+        self.bus.select_controller(Controller::Both)?;
+        for &row in rows {
+            self.write_data(row & command::GLYPH_ROW_MASK, delay)?;
+        }
+
+        let address = self.address;
+        self.set_address(address, delay)
+    }
+
+    fn write_data<D: DelayNs>(
+        &mut self,
+        data: u8,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        self.bus.write(data, true, delay)?;
+
+        // Wait for the data to be processed
+        self.wait_ready(100, delay)
+    }
+
     // Pulse the enable pin telling the LCD1602 that we something for it
     /*fn pulse_enable(&mut self) {
         self.en.set_high();
@@ -551,14 +1457,62 @@ where
     }*/
 }
 
-//impl<B> Write for LCD1602<B>
-//where
-//    B: DataBus,
-//{
-//    fn write_str(&mut self, string: &str) -> Result {
-//        for c in string.chars() {
-//            self.write_char(c, delay);
-//        }
-//        Ok(())
-//    }
-//}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_bus::NoDelay;
+
+    const GLYPH: [u8; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+    #[test]
+    fn create_char_5x10_fills_two_slots() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X1_TYPE2);
+        lcd.set_cursor(3, 0, &mut NoDelay).unwrap();
+        let start = lcd.bus().log.len();
+        lcd.create_char_5x10(1, &GLYPH, &mut NoDelay).unwrap();
+
+        let log = &lcd.bus().log[start..];
+        assert_eq!(log.len(), 18);
+        assert_eq!(log[0], (false, command::SET_CGRAM_ADDRESS | 0x10));
+        for (i, &(data, byte)) in log[1..17].iter().enumerate() {
+            assert!(data);
+            assert_eq!(byte, GLYPH.get(i).copied().unwrap_or(0));
+        }
+        // Back to where the cursor was in DDRAM
+        assert_eq!(log[17], (false, command::SET_DDRAM_ADDRESS | 3));
+
+        assert_eq!(lcd.bus().cgram[..0x10], [0; 0x10]);
+        assert_eq!(lcd.bus().cgram[0x10..0x1A], GLYPH);
+        assert_eq!(lcd.bus().cgram[0x1A..], [0; 0x26]);
+
+        lcd.write_byte(1 << 1, &mut NoDelay).unwrap();
+        assert_eq!(lcd.bus().ddram[3], 2);
+    }
+
+    #[test]
+    fn create_char_5x10_slots() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X1_TYPE2);
+
+        assert!(lcd.create_char_5x10(3, &GLYPH, &mut NoDelay).is_ok());
+        assert_eq!(lcd.bus().cgram[0x30..0x3A], GLYPH);
+        assert_eq!(
+            lcd.create_char_5x10(4, &GLYPH, &mut NoDelay),
+            Err(Error::InvalidCustomCharSlot)
+        );
+    }
+
+    #[test]
+    fn set_font_keeps_interface_width() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X1_TYPE2);
+
+        lcd.set_font(Font::Dots5x10, &mut NoDelay).unwrap();
+        assert_eq!(lcd.bus().log.last(), Some(&(false, 0b0010_0100)));
+
+        lcd.function_set = command::FUNCTION_SET_8BIT;
+        lcd.set_font(Font::Dots5x10, &mut NoDelay).unwrap();
+        assert_eq!(lcd.bus().log.last(), Some(&(false, 0b0011_0100)));
+
+        lcd.set_font(Font::Dots5x8, &mut NoDelay).unwrap();
+        assert_eq!(lcd.bus().log.last(), Some(&(false, 0b0011_1000)));
+    }
+}
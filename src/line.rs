@@ -0,0 +1,159 @@
+use embedded_hal::delay::DelayNs;
+
+use crate::data_bus::DataBus;
+use crate::error::{Error, Result};
+use crate::geometry::MAX_COLUMNS;
+use crate::number_format::Align;
+use crate::LCD1602;
+
+impl<B> LCD1602<B>
+where
+    B: DataBus,
+{
+    /// Write a whole row of the display, aligned and padded with spaces, so
+    /// whatever was on the row before is overwritten without a
+    /// [clear](#method.clear). Text longer than the row is cut off, and control
+    /// characters are written as they are.
+    ///
+    /// The cursor is left at the start of the row.
+    ///
+    /// ```rust,ignore
+    /// lcd.write_line(0, "Settings", Align::Center, &mut delay)?;
+    /// ```
+    pub fn write_line<D: DelayNs>(
+        &mut self,
+        row: u8,
+        text: &str,
+        align: Align,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        let columns = (self.geometry.columns() as usize).min(MAX_COLUMNS);
+        let mut text_codes = [b' '; MAX_COLUMNS];
+        let len = self.encode_cells(text.chars(), &mut text_codes[..columns], delay)?;
+
+        let padding = columns - len;
+        let before = align.before(padding);
+
+        let mut cells = [b' '; MAX_COLUMNS];
+        cells[before..before + len].copy_from_slice(&text_codes[..len]);
+
+        self.write_row(0, row, &cells[..columns], delay)?;
+        self.set_cursor(0, row, delay)
+    }
+
+    /// Blank a row of the display, leaving the cursor at its start.
+    pub fn clear_row<D: DelayNs>(&mut self, row: u8, delay: &mut D) -> Result<(), B::Error> {
+        let columns = (self.geometry.columns() as usize).min(MAX_COLUMNS);
+
+        self.write_row(0, row, &[b' '; MAX_COLUMNS][..columns], delay)?;
+        self.set_cursor(0, row, delay)
+    }
+
+    /// Blank the row the cursor is on from the cursor to the end of the row,
+    /// leaving the cursor where it was. Nothing is written when the cursor is
+    /// not on the display.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_cursor(0, 1, &mut delay)?;
+    /// lcd.write_str("Temp ", &mut delay)?;
+    /// lcd.write_i32(temp, 0, Align::Left, &mut delay)?;
+    /// lcd.clear_to_eol(&mut delay)?;
+    /// ```
+    pub fn clear_to_eol<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), B::Error> {
+        if self.wrap_pending.is_some() {
+            // The row was filled up to its last column
+            return Ok(());
+        }
+
+        let Some((column, row)) = self.geometry.position(self.address) else {
+            return Ok(());
+        };
+
+        let address = self.address;
+        let columns = (self.geometry.columns() as usize).min(MAX_COLUMNS);
+        let blank = columns.saturating_sub(column as usize);
+
+        self.write_row(column, row, &[b' '; MAX_COLUMNS][..blank], delay)?;
+        self.set_cursor_pos(address, delay)
+    }
+
+    // Write codes to consecutive cells of a row, following the geometry where
+    // a row is not contiguous in DDRAM
+    pub(crate) fn write_row<D: DelayNs>(
+        &mut self,
+        column: u8,
+        row: u8,
+        cells: &[u8],
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        for (offset, &code) in cells.iter().enumerate() {
+            let address = self
+                .geometry
+                .address(column + offset as u8, row)
+                .ok_or(Error::OutOfBounds)?;
+
+            if offset == 0 || address != self.address {
+                self.set_cursor_pos(address, delay)?;
+            }
+
+            self.write_byte(code, delay)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_bus::NoDelay;
+    use crate::Geometry;
+
+    #[test]
+    fn write_line_pads() {
+        let geometry = Geometry::LCD16X2;
+        let mut lcd = LCD1602::test(geometry);
+        lcd.write_line(1, "xxxxxxxxxxxxxxxx", Align::Left, &mut NoDelay)
+            .unwrap();
+
+        lcd.write_line(1, "Hi", Align::Center, &mut NoDelay)
+            .unwrap();
+        assert_eq!(&lcd.bus().row(geometry, 1)[..16], b"       Hi       ");
+        lcd.write_line(1, "Hi", Align::Right, &mut NoDelay).unwrap();
+        assert_eq!(&lcd.bus().row(geometry, 1)[..16], b"              Hi");
+
+        // The cursor is back at the start of the row
+        lcd.write_line(1, "", Align::Left, &mut NoDelay).unwrap();
+        lcd.write_str("Ok", &mut NoDelay).unwrap();
+        assert_eq!(&lcd.bus().row(geometry, 1)[..16], b"Ok              ");
+    }
+
+    #[test]
+    fn write_line_truncates() {
+        let geometry = Geometry::LCD16X2;
+        let mut lcd = LCD1602::test(geometry);
+        lcd.write_line(0, "A line too long for the row", Align::Right, &mut NoDelay)
+            .unwrap();
+
+        assert_eq!(&lcd.bus().row(geometry, 0)[..16], b"A line too long ");
+        assert_eq!(lcd.bus().ddram[16], b' ');
+        assert_eq!(&lcd.bus().row(geometry, 1)[..16], b"                ");
+    }
+
+    #[test]
+    fn clear_to_eol_keeps_cursor() {
+        let geometry = Geometry::LCD16X2;
+        let mut lcd = LCD1602::test(geometry);
+        lcd.write_line(1, "Temp 1234567890", Align::Left, &mut NoDelay)
+            .unwrap();
+
+        lcd.set_cursor(5, 1, &mut NoDelay).unwrap();
+        lcd.clear_to_eol(&mut NoDelay).unwrap();
+        assert_eq!(&lcd.bus().row(geometry, 1)[..16], b"Temp            ");
+
+        lcd.write_str("21C", &mut NoDelay).unwrap();
+        lcd.clear_to_eol(&mut NoDelay).unwrap();
+        lcd.write_str("!", &mut NoDelay).unwrap();
+        assert_eq!(&lcd.bus().row(geometry, 1)[..16], b"Temp 21C!       ");
+    }
+}
@@ -0,0 +1,305 @@
+use embedded_hal::delay::DelayNs;
+
+use crate::data_bus::DataBus;
+use crate::error::Result;
+use crate::geometry::MAX_COLUMNS;
+use crate::progress_bar::update_row;
+use crate::LCD1602;
+
+// Spaces between the end of the text and its next repeat when wrapping
+const GAP: &str = "   ";
+
+/// How a [Marquee] moves text that is wider than its region.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarqueeMode {
+    /// The text scrolls left continuously, starting over after a gap.
+    Wrap,
+    /// The text scrolls left until its end shows, then back right to its start.
+    PingPong,
+    /// The text scrolls left until its end shows, then jumps back to its start.
+    PauseAtEnds,
+}
+
+/// Text scrolling through part of one row, leaving the rest of the display
+/// alone, unlike [shift_display](crate::LCD1602::shift_display) which moves
+/// every row.
+///
+/// The marquee does not wait by itself. Call [tick](#method.tick) with the
+/// current time as often as convenient and it redraws its region once the
+/// next step is due. Text that fits the region is shown without scrolling.
+///
+/// ```rust,ignore
+/// let mut ticker = Marquee::new(0, 1, 16, "Now playing: a rather long title", 300);
+/// ticker.set_mode(MarqueeMode::PingPong);
+///
+/// loop {
+///     ticker.tick(&mut lcd, millis(), &mut delay)?;
+///     // other work
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Marquee<'a> {
+    column: u8,
+    row: u8,
+    width: u8,
+    text: &'a str,
+    len: usize,
+    step_ms: u32,
+    pause_ms: u32,
+    mode: MarqueeMode,
+    offset: usize,
+    backwards: bool,
+    // Time of the next step, set once drawn
+    due: Option<u32>,
+    // Codes on the display, valid once drawn
+    cells: [u8; MAX_COLUMNS],
+}
+
+impl<'a> Marquee<'a> {
+    /// Creates a marquee for the cells `column..column + width` of a row,
+    /// moving the text by one column every `step_ms` milliseconds. Widths above
+    /// 40 are cut down to 40.
+    pub fn new(column: u8, row: u8, width: u8, text: &'a str, step_ms: u32) -> Marquee<'a> {
+        Marquee {
+            column,
+            row,
+            width: width.min(MAX_COLUMNS as u8),
+            text,
+            len: text.chars().count(),
+            step_ms,
+            pause_ms: 1000,
+            mode: MarqueeMode::Wrap,
+            offset: 0,
+            backwards: false,
+            due: None,
+            cells: [b' '; MAX_COLUMNS],
+        }
+    }
+
+    /// Replaces the text and starts over from its beginning.
+    pub fn set_text(&mut self, text: &'a str) -> &mut Self {
+        self.text = text;
+        self.len = text.chars().count();
+        self.restart()
+    }
+
+    /// Sets the time in milliseconds between steps.
+    pub fn set_step_ms(&mut self, step_ms: u32) -> &mut Self {
+        self.step_ms = step_ms;
+        self
+    }
+
+    /// Sets how long the text stays at either end in `PingPong` and
+    /// `PauseAtEnds` mode.
+    ///
+    /// Default is 1000 ms.
+    pub fn set_pause_ms(&mut self, pause_ms: u32) -> &mut Self {
+        self.pause_ms = pause_ms;
+        self
+    }
+
+    /// Sets how the text moves and starts over from its beginning.
+    ///
+    /// Default is [MarqueeMode::Wrap].
+    pub fn set_mode(&mut self, mode: MarqueeMode) -> &mut Self {
+        self.mode = mode;
+        self.restart()
+    }
+
+    /// Starts over from the beginning of the text on the next tick, redrawing
+    /// every cell of the region.
+    pub fn restart(&mut self) -> &mut Self {
+        self.offset = 0;
+        self.backwards = false;
+        self.due = None;
+        self
+    }
+
+    /// Draws the region if this is the first tick or the next step is due, and
+    /// returns whether it did. `now_ms` is a free-running millisecond counter,
+    /// which may wrap around.
+    ///
+    /// Returns an error if the region does not fit on the display.
+    pub fn tick<B: DataBus, D: DelayNs>(
+        &mut self,
+        lcd: &mut LCD1602<B>,
+        now_ms: u32,
+        delay: &mut D,
+    ) -> Result<bool, B::Error> {
+        let redraw = match self.due {
+            None => true,
+            Some(due) if (now_ms.wrapping_sub(due) as i32) >= 0 => {
+                self.step();
+                false
+            }
+            Some(_) => return Ok(false),
+        };
+
+        let mut cells = [b' '; MAX_COLUMNS];
+        let width = self.width as usize;
+        lcd.encode_cells(self.window().iter().copied(), &mut cells[..width], delay)?;
+
+        update_row(
+            lcd,
+            self.column,
+            self.row,
+            &cells[..width],
+            &mut self.cells[..width],
+            redraw,
+            delay,
+        )?;
+
+        self.due = Some(now_ms.wrapping_add(self.wait()));
+
+        Ok(true)
+    }
+
+    // Offset at which the end of the text shows at the end of the region
+    fn last_offset(&self) -> usize {
+        self.len.saturating_sub(self.width as usize)
+    }
+
+    fn step(&mut self) {
+        let last = self.last_offset();
+        if last == 0 {
+            return;
+        }
+
+        match self.mode {
+            MarqueeMode::Wrap => self.offset = (self.offset + 1) % (self.len + GAP.len()),
+            MarqueeMode::PauseAtEnds if self.offset >= last => self.offset = 0,
+            MarqueeMode::PauseAtEnds => self.offset += 1,
+            MarqueeMode::PingPong => {
+                if self.backwards {
+                    self.offset -= 1;
+                } else {
+                    self.offset += 1;
+                }
+
+                if self.offset == 0 || self.offset >= last {
+                    self.backwards = !self.backwards;
+                }
+            }
+        }
+    }
+
+    // Time until the next step, longer at either end of the text
+    fn wait(&self) -> u32 {
+        let at_end = self.offset == 0 || self.offset >= self.last_offset();
+        if self.mode != MarqueeMode::Wrap && at_end {
+            self.pause_ms
+        } else {
+            self.step_ms
+        }
+    }
+
+    // The characters shown in the region, blank past the end of the text
+    fn window(&self) -> [char; MAX_COLUMNS] {
+        let mut window = [' '; MAX_COLUMNS];
+
+        if self.mode == MarqueeMode::Wrap && self.last_offset() > 0 {
+            let text = self.text.chars().chain(GAP.chars()).cycle();
+            for (cell, c) in window.iter_mut().zip(text.skip(self.offset)) {
+                *cell = c;
+            }
+        } else {
+            for (cell, c) in window.iter_mut().zip(self.text.chars().skip(self.offset)) {
+                *cell = c;
+            }
+        }
+
+        window
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_bus::{NoDelay, TestBus};
+    use crate::Geometry;
+
+    fn shown(lcd: &LCD1602<TestBus>) -> &[u8] {
+        &lcd.bus().ddram[..4]
+    }
+
+    #[test]
+    fn steps_when_due() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        let mut marquee = Marquee::new(0, 0, 4, "abcdefgh", 100);
+
+        assert_eq!(marquee.tick(&mut lcd, 0, &mut NoDelay), Ok(true));
+        assert_eq!(shown(&lcd), b"abcd");
+        assert_eq!(marquee.tick(&mut lcd, 99, &mut NoDelay), Ok(false));
+        assert_eq!(shown(&lcd), b"abcd");
+        assert_eq!(marquee.tick(&mut lcd, 100, &mut NoDelay), Ok(true));
+        assert_eq!(shown(&lcd), b"bcde");
+
+        // The millisecond counter wraps around
+        marquee.restart();
+        assert_eq!(marquee.tick(&mut lcd, u32::MAX - 9, &mut NoDelay), Ok(true));
+        assert_eq!(marquee.tick(&mut lcd, 89, &mut NoDelay), Ok(false));
+        assert_eq!(marquee.tick(&mut lcd, 90, &mut NoDelay), Ok(true));
+        assert_eq!(shown(&lcd), b"bcde");
+    }
+
+    #[test]
+    fn wrap_starts_over_after_a_gap() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        let mut marquee = Marquee::new(0, 0, 4, "abcdef", 1);
+
+        let mut frames = [[0u8; 4]; 10];
+        for (now, frame) in frames.iter_mut().enumerate() {
+            marquee.tick(&mut lcd, now as u32, &mut NoDelay).unwrap();
+            frame.copy_from_slice(shown(&lcd));
+        }
+
+        assert_eq!(&frames[0], b"abcd");
+        assert_eq!(&frames[2], b"cdef");
+        assert_eq!(&frames[3], b"def ");
+        assert_eq!(&frames[6], b"   a");
+        assert_eq!(&frames[8], b" abc");
+        assert_eq!(&frames[9], b"abcd");
+    }
+
+    #[test]
+    fn ping_pong_turns_at_both_ends() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        let mut marquee = Marquee::new(0, 0, 4, "abcdef", 1);
+        marquee.set_mode(MarqueeMode::PingPong).set_pause_ms(10);
+
+        // Each end is held for the pause, the steps between take 1 ms
+        let ticks: [(u32, &[u8]); 7] = [
+            (0, b"abcd"),
+            (10, b"bcde"),
+            (11, b"cdef"),
+            (21, b"bcde"),
+            (22, b"abcd"),
+            (32, b"bcde"),
+            (33, b"cdef"),
+        ];
+        for (now, expected) in ticks {
+            assert_eq!(marquee.tick(&mut lcd, now, &mut NoDelay), Ok(true));
+            assert_eq!(shown(&lcd), expected, "{now}");
+        }
+        assert_eq!(marquee.tick(&mut lcd, 42, &mut NoDelay), Ok(false));
+    }
+
+    #[test]
+    fn short_text_stays_put() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        let mut marquee = Marquee::new(0, 0, 4, "hi", 1);
+
+        for mode in [MarqueeMode::Wrap, MarqueeMode::PingPong] {
+            marquee.set_mode(mode);
+            marquee.tick(&mut lcd, 0, &mut NoDelay).unwrap();
+            assert_eq!(shown(&lcd), b"hi  ");
+
+            let written = lcd.bus().log.len();
+            for now in 1..5 {
+                marquee.tick(&mut lcd, now * 1000, &mut NoDelay).unwrap();
+            }
+            assert_eq!(shown(&lcd), b"hi  ");
+            assert_eq!(lcd.bus().log.len(), written);
+        }
+    }
+}
@@ -0,0 +1,404 @@
+use embedded_hal::delay::DelayNs;
+
+use crate::data_bus::DataBus;
+use crate::error::Result;
+use crate::geometry::MAX_COLUMNS;
+use crate::progress_bar::update_row;
+use crate::LCD1602;
+
+/// An entry of a [Menu].
+#[derive(Clone, Copy, Debug)]
+pub enum MenuItem<'a> {
+    /// An entry that [Menu::handle] reports by its `id` when selected.
+    Action { label: &'a str, id: u16 },
+    /// An entry that opens a list of further entries when selected.
+    Submenu {
+        label: &'a str,
+        items: &'a [MenuItem<'a>],
+    },
+}
+
+impl<'a> MenuItem<'a> {
+    /// Text shown for the entry.
+    pub fn label(&self) -> &'a str {
+        match *self {
+            MenuItem::Action { label, .. } | MenuItem::Submenu { label, .. } => label,
+        }
+    }
+}
+
+/// Button presses a [Menu] reacts to, fed in by the application from its own
+/// button or encoder handling.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuEvent {
+    /// Move to the entry above
+    Up,
+    /// Move to the entry below
+    Down,
+    /// Run the current entry or open its submenu
+    Select,
+    /// Go back to the parent menu
+    Back,
+}
+
+/// What the application has to do after a [MenuEvent].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuAction {
+    /// The action with this id was selected.
+    Selected(u16),
+    /// `Back` was pressed in the top menu.
+    Exit,
+}
+
+// An open list of entries, with the selected one and the first one shown
+#[derive(Clone, Copy, Debug)]
+struct Level<'a> {
+    items: &'a [MenuItem<'a>],
+    selected: usize,
+    top: usize,
+}
+
+/// A list of entries filling the display, one per row, with the selected one
+/// marked by a cursor character in the first column. Entries can open
+/// submenus up to `DEPTH` levels deep, counting the top menu, which must be
+/// at least 1 or [new](#method.new) doesn't compile. The list scrolls to keep
+/// the selected entry in view.
+///
+/// ```rust,ignore
+/// const NETWORK: &[MenuItem] = &[
+///     MenuItem::Action { label: "DHCP", id: 10 },
+///     MenuItem::Action { label: "Static IP", id: 11 },
+/// ];
+/// const SETTINGS: &[MenuItem] = &[
+///     MenuItem::Submenu { label: "Network", items: NETWORK },
+///     MenuItem::Action { label: "Contrast", id: 1 },
+///     MenuItem::Action { label: "Reboot", id: 2 },
+/// ];
+///
+/// let mut menu: Menu = Menu::new(SETTINGS);
+/// loop {
+///     menu.draw(&mut lcd, &mut delay)?;
+///     match menu.handle(buttons.wait_event()) {
+///         Some(MenuAction::Selected(id)) => run(id),
+///         Some(MenuAction::Exit) => break,
+///         None => {}
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Menu<'a, const DEPTH: usize = 4> {
+    levels: [Level<'a>; DEPTH],
+    depth: usize,
+    cursor: u8,
+    dirty: bool,
+    // Codes on the display per row, valid once drawn
+    cells: [[u8; MAX_COLUMNS]; 4],
+    drawn: bool,
+}
+
+impl<'a, const DEPTH: usize> Menu<'a, DEPTH> {
+    /// Creates a menu showing `items`, with the first one selected.
+    pub fn new(items: &'a [MenuItem<'a>]) -> Menu<'a, DEPTH> {
+        const { assert!(DEPTH > 0, "a menu needs at least one level") };
+
+        let level = Level {
+            items,
+            selected: 0,
+            top: 0,
+        };
+
+        Menu {
+            levels: [level; DEPTH],
+            depth: 1,
+            cursor: b'>',
+            dirty: true,
+            cells: [[b' '; MAX_COLUMNS]; 4],
+            drawn: false,
+        }
+    }
+
+    /// Sets the character code marking the selected entry, which may be a
+    /// custom character slot.
+    ///
+    /// Default is `>`.
+    pub fn set_cursor_char(&mut self, code: u8) -> &mut Self {
+        self.cursor = code;
+        self.dirty = true;
+        self
+    }
+
+    /// Returns the selected entry, or `None` if the open list is empty.
+    pub fn selected(&self) -> Option<&'a MenuItem<'a>> {
+        let level = self.level();
+        level.items.get(level.selected)
+    }
+
+    /// Returns how many submenus are open, `0` in the top menu.
+    pub fn depth(&self) -> usize {
+        self.depth.saturating_sub(1)
+    }
+
+    /// Closes all submenus and selects the first entry of the top menu.
+    pub fn reset(&mut self) {
+        self.depth = 1;
+        self.levels[0].selected = 0;
+        self.levels[0].top = 0;
+        self.dirty = true;
+    }
+
+    /// Makes the next draw rewrite the display, for when it has been cleared or
+    /// written over.
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+        self.drawn = false;
+    }
+
+    /// Updates the menu for a button press, and returns what the application
+    /// has to do, if anything. Submenus past `DEPTH` levels are not opened.
+    pub fn handle(&mut self, event: MenuEvent) -> Option<MenuAction> {
+        let depth = self.depth;
+        let level = self.level_mut();
+
+        match event {
+            MenuEvent::Up if level.selected > 0 => level.selected -= 1,
+            MenuEvent::Down if level.selected + 1 < level.items.len() => level.selected += 1,
+            MenuEvent::Select => match level.items.get(level.selected) {
+                Some(&MenuItem::Action { id, .. }) => return Some(MenuAction::Selected(id)),
+                Some(&MenuItem::Submenu { items, .. }) if depth < DEPTH => {
+                    self.levels[depth] = Level {
+                        items,
+                        selected: 0,
+                        top: 0,
+                    };
+                    self.depth += 1;
+                }
+                _ => return None,
+            },
+            MenuEvent::Back if depth > 1 => self.depth -= 1,
+            MenuEvent::Back => return Some(MenuAction::Exit),
+            _ => return None,
+        }
+
+        self.dirty = true;
+
+        None
+    }
+
+    /// Draws the open list over the whole display if it changed since the last
+    /// draw, rewriting only the cells that changed. Labels longer than a row are
+    /// cut off.
+    pub fn draw<B: DataBus, D: DelayNs>(
+        &mut self,
+        lcd: &mut LCD1602<B>,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        let geometry = lcd.geometry();
+        let rows = geometry.rows().min(4) as usize;
+        let columns = (geometry.columns() as usize).min(MAX_COLUMNS);
+
+        // Scroll just far enough to show the selected entry
+        let level = self.level_mut();
+        if level.selected < level.top {
+            level.top = level.selected;
+        } else if level.selected >= level.top + rows {
+            level.top = level.selected + 1 - rows;
+        }
+        let level = *level;
+
+        for row in 0..rows {
+            let mut cells = [b' '; MAX_COLUMNS];
+
+            let index = level.top + row;
+            if let Some(item) = level.items.get(index) {
+                if index == level.selected {
+                    cells[0] = self.cursor;
+                }
+
+                let label = &mut cells[1..columns.max(1)];
+                lcd.encode_cells(item.label().chars(), label, delay)?;
+            }
+
+            update_row(
+                lcd,
+                0,
+                row as u8,
+                &cells[..columns],
+                &mut self.cells[row][..columns],
+                !self.drawn,
+                delay,
+            )?;
+        }
+
+        self.dirty = false;
+        self.drawn = true;
+
+        Ok(())
+    }
+
+    fn level(&self) -> &Level<'a> {
+        &self.levels[self.depth - 1]
+    }
+
+    fn level_mut(&mut self) -> &mut Level<'a> {
+        &mut self.levels[self.depth - 1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command;
+    use crate::test_bus::{NoDelay, TestBus};
+    use crate::Geometry;
+
+    const COLORS: &[MenuItem] = &[
+        MenuItem::Action {
+            label: "Red",
+            id: 10,
+        },
+        MenuItem::Action {
+            label: "Blue",
+            id: 11,
+        },
+    ];
+    const ITEMS: &[MenuItem] = &[
+        MenuItem::Action {
+            label: "One",
+            id: 1,
+        },
+        MenuItem::Submenu {
+            label: "Colors",
+            items: COLORS,
+        },
+        MenuItem::Action {
+            label: "Three",
+            id: 3,
+        },
+        MenuItem::Action {
+            label: "Four",
+            id: 4,
+        },
+    ];
+
+    fn label<'a>(menu: &Menu<'a>) -> &'a str {
+        match menu.selected() {
+            Some(item) => item.label(),
+            None => "",
+        }
+    }
+
+    fn rows(lcd: &LCD1602<TestBus>) -> [[u8; 16]; 2] {
+        let geometry = lcd.geometry();
+        let mut rows = [[0; 16]; 2];
+        for (row, cells) in rows.iter_mut().enumerate() {
+            cells.copy_from_slice(&lcd.bus().row(geometry, row as u8)[..16]);
+        }
+        rows
+    }
+
+    #[test]
+    fn stops_at_first_and_last() {
+        let mut menu: Menu = Menu::new(ITEMS);
+        assert_eq!(menu.handle(MenuEvent::Up), None);
+        assert_eq!(label(&menu), "One");
+
+        for _ in 0..6 {
+            menu.handle(MenuEvent::Down);
+        }
+        assert_eq!(label(&menu), "Four");
+
+        menu.handle(MenuEvent::Up);
+        assert_eq!(label(&menu), "Three");
+    }
+
+    #[test]
+    fn enters_and_leaves_submenus() {
+        let mut menu: Menu = Menu::new(ITEMS);
+        assert_eq!(
+            menu.handle(MenuEvent::Select),
+            Some(MenuAction::Selected(1))
+        );
+
+        menu.handle(MenuEvent::Down);
+        assert_eq!(menu.handle(MenuEvent::Select), None);
+        assert_eq!(menu.depth(), 1);
+        assert_eq!(label(&menu), "Red");
+
+        menu.handle(MenuEvent::Down);
+        assert_eq!(
+            menu.handle(MenuEvent::Select),
+            Some(MenuAction::Selected(11))
+        );
+
+        // Back returns to the entry the submenu was opened from
+        assert_eq!(menu.handle(MenuEvent::Back), None);
+        assert_eq!(menu.depth(), 0);
+        assert_eq!(label(&menu), "Colors");
+        assert_eq!(menu.handle(MenuEvent::Back), Some(MenuAction::Exit));
+
+        let mut flat: Menu<1> = Menu::new(ITEMS);
+        flat.handle(MenuEvent::Down);
+        assert_eq!(flat.handle(MenuEvent::Select), None);
+        assert_eq!(flat.depth(), 0);
+        assert_eq!(flat.selected().map(MenuItem::label), Some("Colors"));
+    }
+
+    #[test]
+    fn scrolls_to_the_selection() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        let mut menu: Menu = Menu::new(ITEMS);
+        menu.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(rows(&lcd), [*b">One            ", *b" Colors         "]);
+
+        menu.handle(MenuEvent::Down);
+        menu.handle(MenuEvent::Down);
+        menu.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(rows(&lcd), [*b" Colors         ", *b">Three          "]);
+
+        menu.handle(MenuEvent::Down);
+        menu.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(rows(&lcd), [*b" Three          ", *b">Four           "]);
+
+        for _ in 0..3 {
+            menu.handle(MenuEvent::Up);
+        }
+        menu.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(rows(&lcd), [*b">One            ", *b" Colors         "]);
+    }
+
+    #[test]
+    fn redraws_only_the_marker() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        let mut menu: Menu = Menu::new(ITEMS);
+        menu.draw(&mut lcd, &mut NoDelay).unwrap();
+
+        let start = lcd.bus().log.len();
+        menu.handle(MenuEvent::Down);
+        menu.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(
+            &lcd.bus().log[start..],
+            &[
+                (false, command::SET_DDRAM_ADDRESS),
+                (true, b' '),
+                (false, command::SET_DDRAM_ADDRESS | 0x40),
+                (true, b'>'),
+            ]
+        );
+
+        // Nothing changed, nothing is written
+        let start = lcd.bus().log.len();
+        menu.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(lcd.bus().log.len(), start);
+
+        menu.set_cursor_char(0x7E);
+        menu.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(
+            &lcd.bus().log[start..],
+            &[(false, command::SET_DDRAM_ADDRESS | 0x40), (true, 0x7E)]
+        );
+    }
+}
@@ -0,0 +1,390 @@
+use embedded_hal::delay::DelayNs;
+
+use crate::data_bus::DataBus;
+use crate::error::Result;
+use crate::LCD1602;
+
+// Longest text a number can take up, a u64 with sign, separator and prefix
+const MAX_LEN: usize = 24;
+// Most decimals a fixed point number can have, all of an i32
+const MAX_DECIMALS: u8 = 9;
+const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Where text goes in a field wider than itself. The rest of the field is
+/// filled with spaces.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    /// Text starts at the first column of the field.
+    Left,
+    /// Text ends at the last column of the field.
+    #[default]
+    Right,
+    /// Text is centered, one column further left if it can't be exact.
+    Center,
+}
+
+impl Align {
+    // Spaces that go before the text, out of `padding` in total
+    pub(crate) fn before(self, padding: usize) -> usize {
+        match self {
+            Align::Left => 0,
+            Align::Right => padding,
+            Align::Center => padding / 2,
+        }
+    }
+}
+
+// Text of a number, built from its last character to its first
+struct Digits {
+    buf: [u8; MAX_LEN],
+    start: usize,
+}
+
+impl Digits {
+    fn new() -> Digits {
+        Digits {
+            buf: [b' '; MAX_LEN],
+            start: MAX_LEN,
+        }
+    }
+
+    fn push(&mut self, byte: u8) {
+        if self.start > 0 {
+            self.start -= 1;
+            self.buf[self.start] = byte;
+        }
+    }
+
+    // Pushes `value` in a base, with at least `min_digits` digits
+    fn push_number(&mut self, mut value: u64, base: u64, min_digits: u8) {
+        let mut digits = 0;
+        while value > 0 || digits < min_digits.max(1) {
+            self.push(DIGITS[(value % base) as usize]);
+            value /= base;
+            digits += 1;
+        }
+    }
+
+    // Pushes `value / 10^decimals` with all decimals and at least one integer digit
+    fn push_fixed(&mut self, value: u64, decimals: u8, separator: u8) {
+        if decimals == 0 {
+            return self.push_number(value, 10, 1);
+        }
+
+        let scale = 10u64.pow(decimals as u32);
+        self.push_number(value % scale, 10, decimals);
+        self.push(separator);
+        self.push_number(value / scale, 10, 1);
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.buf[self.start..]
+    }
+}
+
+// The SI prefix as a power of ten, `magnitude * 10^exponent` scaled to that
+// prefix and the decimals of the scaled value, for three significant digits.
+// `None` if the value is too large for any prefix.
+fn si_scale(magnitude: u64, exponent: i8) -> Option<(i32, u64, u8)> {
+    if magnitude == 0 {
+        return Some((0, 0, 0));
+    }
+
+    // Power of ten of the leading digit, and of the prefix below it
+    let leading = magnitude.ilog10() as i32 + exponent as i32;
+    let prefix = (leading.div_euclid(3) * 3).clamp(-6, 6);
+    let integer_digits = leading - prefix + 1;
+    let decimals = (3 - integer_digits).clamp(0, 2);
+
+    let shift = exponent as i32 - prefix + decimals;
+    let scaled = if shift >= 0 {
+        10u64
+            .checked_pow(shift as u32)
+            .and_then(|scale| magnitude.checked_mul(scale))?
+    } else {
+        10u64
+            .checked_pow(shift.unsigned_abs())
+            .map_or(0, |scale| magnitude / scale)
+    };
+
+    Some((prefix, scaled, decimals as u8))
+}
+
+impl<B> LCD1602<B>
+where
+    B: DataBus,
+{
+    /// Set the character code written between the integer and the decimals of
+    /// a number, e.g. `b','`. Defaults to `.`.
+    pub fn set_decimal_separator(&mut self, separator: u8) {
+        self.decimal_separator = separator;
+    }
+
+    /// Returns the character code written between the integer and the decimals
+    /// of a number
+    pub fn decimal_separator(&self) -> u8 {
+        self.decimal_separator
+    }
+
+    /// Write an unsigned number in a field `width` columns wide, padded with
+    /// spaces so it overwrites a longer number written there before.
+    ///
+    /// A width of `0` makes the field as wide as the number. A number that
+    /// doesn't fit the field fills it with `#` instead.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_cursor(12, 0, &mut delay)?;
+    /// lcd.write_u32(rpm, 4, Align::Right, &mut delay)?;
+    /// ```
+    pub fn write_u32<D: DelayNs>(
+        &mut self,
+        value: u32,
+        width: u8,
+        align: Align,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        let mut digits = Digits::new();
+        digits.push_number(value as u64, 10, 1);
+
+        self.write_field(digits.as_bytes(), width, align, delay)
+    }
+
+    /// Write a signed number in a field, see [write_u32](#method.write_u32).
+    pub fn write_i32<D: DelayNs>(
+        &mut self,
+        value: i32,
+        width: u8,
+        align: Align,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        self.write_fixed(value, 0, width, align, delay)
+    }
+
+    /// Write `value / 10^decimals` with all its decimals in a field, see
+    /// [write_u32](#method.write_u32). More than 9 decimals fill the field with
+    /// `#`, like a number that doesn't fit.
+    ///
+    /// ```rust,ignore
+    /// // 21.5, from a reading in tenths of a degree
+    /// lcd.write_fixed(215, 1, 5, Align::Right, &mut delay)?;
+    /// ```
+    pub fn write_fixed<D: DelayNs>(
+        &mut self,
+        value: i32,
+        decimals: u8,
+        width: u8,
+        align: Align,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        if decimals > MAX_DECIMALS {
+            return self.write_overflow(width, delay);
+        }
+
+        let mut digits = Digits::new();
+        digits.push_fixed(
+            value.unsigned_abs() as u64,
+            decimals,
+            self.decimal_separator,
+        );
+        if value < 0 {
+            digits.push(b'-');
+        }
+
+        self.write_field(digits.as_bytes(), width, align, delay)
+    }
+
+    /// Write a number in upper case hexadecimal, without a prefix, in a field,
+    /// see [write_u32](#method.write_u32).
+    pub fn write_hex<D: DelayNs>(
+        &mut self,
+        value: u32,
+        width: u8,
+        align: Align,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        let mut digits = Digits::new();
+        digits.push_number(value as u64, 16, 1);
+
+        self.write_field(digits.as_bytes(), width, align, delay)
+    }
+
+    /// Write `value * 10^exponent` with an SI prefix (`µ`, `m`, `k` or `M`)
+    /// and three significant digits, rounded down, in a field, see
+    /// [write_u32](#method.write_u32). The unit is left to the caller.
+    ///
+    /// `µ` is written as its code in the [character ROM](#method.set_character_rom),
+    /// or as `u` without one, since the code differs between ROMs.
+    ///
+    /// ```rust,ignore
+    /// // 4.70k, from a resistance in ohms
+    /// lcd.write_si(4700, 0, 5, Align::Right, &mut delay)?;
+    /// // 12.5m, from a current in microamps
+    /// lcd.write_si(12_500, -6, 5, Align::Right, &mut delay)?;
+    /// lcd.write_str("A", &mut delay)?;
+    /// ```
+    pub fn write_si<D: DelayNs>(
+        &mut self,
+        value: i32,
+        exponent: i8,
+        width: u8,
+        align: Align,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        let Some((prefix, scaled, decimals)) = si_scale(value.unsigned_abs() as u64, exponent)
+        else {
+            return self.write_overflow(width, delay);
+        };
+
+        let mut digits = Digits::new();
+        match prefix {
+            -6 if self.rom.is_none() => digits.push(b'u'),
+            -6 => {
+                let micro = self.encode('µ', delay)?;
+                digits.push(micro);
+            }
+            -3 => digits.push(b'm'),
+            3 => digits.push(b'k'),
+            6 => digits.push(b'M'),
+            _ => {}
+        }
+        digits.push_fixed(scaled, decimals, self.decimal_separator);
+        if value < 0 {
+            digits.push(b'-');
+        }
+
+        self.write_field(digits.as_bytes(), width, align, delay)
+    }
+
+    // Fill a field with `#`, for a number that can't be written in it
+    fn write_overflow<D: DelayNs>(&mut self, width: u8, delay: &mut D) -> Result<(), B::Error> {
+        for _ in 0..width.max(1) {
+            self.write_byte(b'#', delay)?;
+        }
+
+        Ok(())
+    }
+
+    // Write text padded with spaces to `width` columns, or `#` if it doesn't fit
+    fn write_field<D: DelayNs>(
+        &mut self,
+        text: &[u8],
+        width: u8,
+        align: Align,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        let width = match width {
+            0 => text.len(),
+            width => width as usize,
+        };
+
+        if text.len() > width {
+            for _ in 0..width {
+                self.write_byte(b'#', delay)?;
+            }
+            return Ok(());
+        }
+
+        let padding = width - text.len();
+        let before = align.before(padding);
+
+        for _ in 0..before {
+            self.write_byte(b' ', delay)?;
+        }
+        self.write_bytes(text, delay)?;
+        for _ in before..padding {
+            self.write_byte(b' ', delay)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_bus::{NoDelay, TestBus};
+    use crate::{Geometry, RomA00};
+
+    fn fixed(value: u64, decimals: u8) -> Digits {
+        let mut digits = Digits::new();
+        digits.push_fixed(value, decimals, b'.');
+        digits
+    }
+
+    #[test]
+    fn push_fixed() {
+        assert_eq!(fixed(0, 0).as_bytes(), b"0");
+        assert_eq!(fixed(215, 0).as_bytes(), b"215");
+        assert_eq!(fixed(215, 1).as_bytes(), b"21.5");
+        assert_eq!(fixed(5, 2).as_bytes(), b"0.05");
+        assert_eq!(fixed(0, 3).as_bytes(), b"0.000");
+        assert_eq!(
+            fixed(u32::MAX as u64, MAX_DECIMALS).as_bytes(),
+            b"4.294967295"
+        );
+    }
+
+    #[test]
+    fn si_prefix_and_shift() {
+        assert_eq!(si_scale(0, 0), Some((0, 0, 0)));
+        assert_eq!(si_scale(0, 100), Some((0, 0, 0)));
+        assert_eq!(si_scale(1, 0), Some((0, 100, 2)));
+        assert_eq!(si_scale(999, 0), Some((0, 999, 0)));
+        assert_eq!(si_scale(1000, 0), Some((3, 100, 2)));
+        assert_eq!(si_scale(4700, 0), Some((3, 470, 2)));
+        assert_eq!(si_scale(999_999, 0), Some((3, 999, 0)));
+        assert_eq!(si_scale(1_000_000, 0), Some((6, 100, 2)));
+        assert_eq!(si_scale(12_500, -6), Some((-3, 125, 1)));
+        assert_eq!(si_scale(999, -3), Some((-3, 999, 0)));
+        assert_eq!(si_scale(1, -3), Some((-3, 100, 2)));
+        assert_eq!(si_scale(5, -7), Some((-6, 50, 2)));
+    }
+
+    #[test]
+    fn si_extreme_exponents() {
+        // Past the largest prefix the integer part grows
+        assert_eq!(si_scale(1, 9), Some((6, 1000, 0)));
+        assert_eq!(si_scale(1, 127), None);
+        assert_eq!(si_scale(i32::MIN.unsigned_abs() as u64, 20), None);
+        // Past the smallest prefix the value rounds down to zero
+        assert_eq!(si_scale(1, -9), Some((-6, 0, 2)));
+        assert_eq!(si_scale(1, -128), Some((-6, 0, 2)));
+        assert_eq!(
+            si_scale(i32::MIN.unsigned_abs() as u64, -128),
+            Some((-6, 0, 2))
+        );
+    }
+
+    fn written(lcd: &LCD1602<TestBus>, len: usize) -> &[u8] {
+        &lcd.bus().ddram[..len]
+    }
+
+    #[test]
+    fn write_si() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        lcd.write_si(-1500, 0, 6, Align::Right, &mut NoDelay)
+            .unwrap();
+        lcd.write_si(0, -6, 2, Align::Left, &mut NoDelay).unwrap();
+        assert_eq!(written(&lcd, 8), b"-1.50k0 ");
+
+        lcd.clear(&mut NoDelay).unwrap();
+        lcd.write_si(47, -6, 0, Align::Right, &mut NoDelay).unwrap();
+        lcd.write_si(1, 127, 3, Align::Right, &mut NoDelay).unwrap();
+        assert_eq!(written(&lcd, 8), b"47.0u###");
+
+        lcd.clear(&mut NoDelay).unwrap();
+        lcd.set_character_rom(&RomA00);
+        lcd.write_si(47, -6, 0, Align::Right, &mut NoDelay).unwrap();
+        assert_eq!(written(&lcd, 5), b"47.0\xE4");
+    }
+
+    #[test]
+    fn write_fixed_too_many_decimals() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        lcd.write_fixed(-5, 2, 0, Align::Right, &mut NoDelay)
+            .unwrap();
+        lcd.write_fixed(5, 10, 3, Align::Right, &mut NoDelay)
+            .unwrap();
+        assert_eq!(written(&lcd, 8), b"-0.05###");
+    }
+}
@@ -0,0 +1,324 @@
+use embedded_hal::delay::DelayNs;
+
+use crate::data_bus::DataBus;
+use crate::error::{Error, Result};
+use crate::geometry::MAX_COLUMNS;
+use crate::LCD1602;
+
+// Pixel columns in a character cell
+const CELL_PIXELS: u32 = 5;
+// Cells taken up by the percentage label, e.g. " 42%"
+const LABEL_WIDTH: u8 = 4;
+
+/// A horizontal bar on one row of the display, filled in steps of one pixel
+/// column, for progress and level readings.
+///
+/// The bar uses five consecutive CGRAM slots, starting at slot 0 unless set
+/// otherwise, which [load](#method.load) fills in. Updating the value only
+/// rewrites the cells that changed.
+///
+/// ```rust,ignore
+/// let mut bar = ProgressBar::new(0, 1, 16);
+/// bar.set_end_caps(true).set_label(true);
+/// bar.load(&mut lcd, &mut delay)?;
+///
+/// for done in 0..=100 {
+///     bar.set_value(&mut lcd, done, 100, &mut delay)?;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ProgressBar {
+    column: u8,
+    row: u8,
+    width: u8,
+    first_slot: u8,
+    end_caps: bool,
+    label: bool,
+    // Codes on the display, valid once drawn
+    cells: [u8; MAX_COLUMNS],
+    drawn: bool,
+}
+
+impl ProgressBar {
+    /// Creates a bar `width` cells wide starting at a column and row of the display.
+    /// Widths above 40 are cut down to 40.
+    pub fn new(column: u8, row: u8, width: u8) -> ProgressBar {
+        ProgressBar {
+            column,
+            row,
+            width: width.min(MAX_COLUMNS as u8),
+            first_slot: 0,
+            end_caps: false,
+            label: false,
+            cells: [b' '; MAX_COLUMNS],
+            drawn: false,
+        }
+    }
+
+    /// Sets the first of the five CGRAM slots the bar uses, 0 to 3. Slots above
+    /// 3 are cut down to 3, so the bar stays in CGRAM.
+    ///
+    /// Default is `0`.
+    pub fn set_first_slot(&mut self, slot: u8) -> &mut Self {
+        self.first_slot = slot.min(3);
+        self.drawn = false;
+        self
+    }
+
+    /// Sets whether the bar is put between `[` and `]`.
+    ///
+    /// Default is `false`.
+    pub fn set_end_caps(&mut self, end_caps: bool) -> &mut Self {
+        self.end_caps = end_caps;
+        self.drawn = false;
+        self
+    }
+
+    /// Sets whether the value is shown as a percentage in the last four cells.
+    ///
+    /// Default is `false`.
+    pub fn set_label(&mut self, label: bool) -> &mut Self {
+        self.label = label;
+        self.drawn = false;
+        self
+    }
+
+    /// Makes the next update redraw every cell, for when the display has been
+    /// cleared or written over.
+    pub fn invalidate(&mut self) {
+        self.drawn = false;
+    }
+
+    /// Stores the five glyphs of the bar in its CGRAM slots.
+    pub fn load<B: DataBus, D: DelayNs>(
+        &self,
+        lcd: &mut LCD1602<B>,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        for columns in 1..=CELL_PIXELS as u8 {
+            // Fill from the left, leaving the top and bottom row blank
+            let row = (0b11111 << (CELL_PIXELS as u8 - columns)) & 0b11111;
+            let glyph = [0, row, row, row, row, row, row, 0];
+
+            lcd.create_char(self.first_slot + columns - 1, &glyph, delay)?;
+        }
+
+        Ok(())
+    }
+
+    /// Shows `value` out of `max`, rewriting only the cells that changed.
+    /// Values above `max` show a full bar.
+    ///
+    /// Returns an error if the bar does not fit on the display.
+    pub fn set_value<B: DataBus, D: DelayNs>(
+        &mut self,
+        lcd: &mut LCD1602<B>,
+        value: u32,
+        max: u32,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        let cells = self.render(value, max);
+        let width = self.width as usize;
+
+        update_row(
+            lcd,
+            self.column,
+            self.row,
+            &cells[..width],
+            &mut self.cells[..width],
+            !self.drawn,
+            delay,
+        )?;
+
+        self.drawn = true;
+
+        Ok(())
+    }
+
+    fn render(&self, value: u32, max: u32) -> [u8; MAX_COLUMNS] {
+        let mut cells = [b' '; MAX_COLUMNS];
+        let mut start = 0;
+        let mut end = self.width;
+
+        if self.label && end >= LABEL_WIDTH {
+            end -= LABEL_WIDTH;
+
+            let percent = fraction(value, max, 100) as u8;
+            let label = &mut cells[end as usize..self.width as usize];
+            label[3] = b'%';
+            label[2] = b'0' + percent % 10;
+            if percent >= 10 {
+                label[1] = b'0' + percent / 10 % 10;
+            }
+            if percent >= 100 {
+                label[0] = b'1';
+            }
+        }
+
+        if self.end_caps && end >= 2 {
+            cells[start as usize] = b'[';
+            cells[end as usize - 1] = b']';
+            start += 1;
+            end -= 1;
+        }
+
+        let bar = &mut cells[start as usize..end as usize];
+        let mut pixels = fraction(value, max, bar.len() as u32 * CELL_PIXELS);
+        for cell in bar.iter_mut() {
+            let filled = pixels.min(CELL_PIXELS);
+            if filled > 0 {
+                *cell = self.first_slot + filled as u8 - 1;
+            }
+            pixels -= filled;
+        }
+
+        cells
+    }
+}
+
+// `value / max` scaled to `0..=scale`, rounded down
+fn fraction(value: u32, max: u32, scale: u32) -> u32 {
+    if max == 0 {
+        return scale;
+    }
+
+    (value.min(max) as u64 * scale as u64 / max as u64) as u32
+}
+
+// Writes the cells of a row that differ from `shown`, or all of them on a
+// redraw, and keeps `shown` up to date
+pub(crate) fn update_row<B: DataBus, D: DelayNs>(
+    lcd: &mut LCD1602<B>,
+    column: u8,
+    row: u8,
+    cells: &[u8],
+    shown: &mut [u8],
+    redraw: bool,
+    delay: &mut D,
+) -> Result<(), B::Error> {
+    let geometry = lcd.geometry();
+
+    // DDRAM address of the next write, set once a cell has been written
+    let mut next = None;
+    for (index, (&code, old)) in cells.iter().zip(shown.iter_mut()).enumerate() {
+        if !redraw && *old == code {
+            continue;
+        }
+
+        let Some(address) = geometry.address(column.saturating_add(index as u8), row) else {
+            return Err(Error::OutOfBounds);
+        };
+
+        if next != Some(address) {
+            lcd.set_cursor_pos(address, delay)?;
+        }
+
+        lcd.write_byte(code, delay)?;
+        *old = code;
+        next = Some(address.wrapping_add(1));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command;
+    use crate::test_bus::{NoDelay, TestBus};
+    use crate::Geometry;
+
+    fn shown(lcd: &LCD1602<TestBus>, width: usize) -> &[u8] {
+        &lcd.bus().ddram[..width]
+    }
+
+    #[test]
+    fn partial_cells() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        let mut bar = ProgressBar::new(0, 0, 4);
+
+        // 7 of 20 pixel columns, a full cell and two columns of the next
+        bar.set_value(&mut lcd, 7, 20, &mut NoDelay).unwrap();
+        assert_eq!(shown(&lcd, 4), &[4, 1, b' ', b' ']);
+
+        bar.set_first_slot(2);
+        bar.set_value(&mut lcd, 7, 20, &mut NoDelay).unwrap();
+        assert_eq!(shown(&lcd, 4), &[6, 3, b' ', b' ']);
+
+        // 1 of 3 rounds down to 6 of 20 columns
+        bar.set_value(&mut lcd, 1, 3, &mut NoDelay).unwrap();
+        assert_eq!(shown(&lcd, 4), &[6, 2, b' ', b' ']);
+    }
+
+    #[test]
+    fn empty_and_full() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        let mut bar = ProgressBar::new(0, 0, 6);
+        bar.set_end_caps(true);
+
+        bar.set_value(&mut lcd, 0, 100, &mut NoDelay).unwrap();
+        assert_eq!(shown(&lcd, 6), b"[    ]");
+
+        bar.set_value(&mut lcd, 100, 100, &mut NoDelay).unwrap();
+        assert_eq!(shown(&lcd, 6), &[b'[', 4, 4, 4, 4, b']']);
+
+        bar.set_value(&mut lcd, 0, 100, &mut NoDelay).unwrap();
+        bar.set_value(&mut lcd, 250, 100, &mut NoDelay).unwrap();
+        assert_eq!(shown(&lcd, 6), &[b'[', 4, 4, 4, 4, b']']);
+
+        bar.set_value(&mut lcd, 0, 0, &mut NoDelay).unwrap();
+        assert_eq!(shown(&lcd, 6), &[b'[', 4, 4, 4, 4, b']']);
+    }
+
+    #[test]
+    fn label() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        let mut bar = ProgressBar::new(0, 0, 8);
+        bar.set_label(true);
+
+        bar.set_value(&mut lcd, 0, 100, &mut NoDelay).unwrap();
+        assert_eq!(shown(&lcd, 8), b"      0%");
+        bar.set_value(&mut lcd, 42, 100, &mut NoDelay).unwrap();
+        assert_eq!(shown(&lcd, 8), &[4, 2, b' ', b' ', b' ', b'4', b'2', b'%']);
+        bar.set_value(&mut lcd, 100, 100, &mut NoDelay).unwrap();
+        assert_eq!(shown(&lcd, 8), &[4, 4, 4, 4, b'1', b'0', b'0', b'%']);
+    }
+
+    #[test]
+    fn redraws_only_changed_cells() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        let mut bar = ProgressBar::new(2, 1, 4);
+        bar.set_value(&mut lcd, 7, 20, &mut NoDelay).unwrap();
+
+        let start = lcd.bus().log.len();
+        bar.set_value(&mut lcd, 8, 20, &mut NoDelay).unwrap();
+        assert_eq!(
+            &lcd.bus().log[start..],
+            &[(false, command::SET_DDRAM_ADDRESS | 0x43), (true, 2)]
+        );
+
+        let start = lcd.bus().log.len();
+        bar.set_value(&mut lcd, 8, 20, &mut NoDelay).unwrap();
+        assert_eq!(lcd.bus().log.len(), start);
+
+        // After invalidate every cell is written again
+        bar.invalidate();
+        bar.set_value(&mut lcd, 8, 20, &mut NoDelay).unwrap();
+        let data = lcd.bus().log[start..].iter().filter(|&&(data, _)| data);
+        assert_eq!(data.count(), 4);
+    }
+
+    #[test]
+    fn first_slot_stays_in_cgram() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        let mut bar = ProgressBar::new(0, 0, 2);
+        bar.set_first_slot(255);
+
+        bar.set_value(&mut lcd, 10, 10, &mut NoDelay).unwrap();
+        assert_eq!(shown(&lcd, 2), &[7, 7]);
+
+        bar.load(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(lcd.bus().cgram[..24], [0; 24]);
+        assert_eq!(lcd.bus().cgram[7 * 8 + 1], 0b11111);
+    }
+}
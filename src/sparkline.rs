@@ -0,0 +1,289 @@
+use embedded_hal::delay::DelayNs;
+
+use crate::data_bus::DataBus;
+use crate::error::Result;
+use crate::geometry::MAX_COLUMNS;
+use crate::progress_bar::update_row;
+use crate::LCD1602;
+
+// Bar heights a single cell can show, one per CGRAM slot
+const CELL_LEVELS: u8 = 8;
+
+/// How samples are mapped to bar heights by a [Sparkline].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scale {
+    /// The lowest and highest of the shown samples span the full height.
+    Auto,
+    /// `min` and `max` span the full height, samples outside are clamped.
+    Fixed { min: i32, max: i32 },
+}
+
+/// A graph of the most recent samples as vertical bars, one cell per sample
+/// with the newest on the right, for a history of readings such as a
+/// temperature.
+///
+/// Up to `N` samples are kept in a ring buffer. The graph is one or two rows
+/// high, giving 8 or 16 bar heights, and uses all eight CGRAM slots, which
+/// [load](#method.load) fills in.
+///
+/// ```rust,ignore
+/// let mut graph: Sparkline<16> = Sparkline::new(0, 0, 16, 2);
+/// graph.load(&mut lcd, &mut delay)?;
+///
+/// loop {
+///     graph.push(sensor.read_temperature());
+///     graph.draw(&mut lcd, &mut delay)?;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sparkline<const N: usize> {
+    column: u8,
+    row: u8,
+    width: u8,
+    height: u8,
+    scale: Scale,
+    samples: [i32; N],
+    // Number of samples stored, and the index the next one goes to
+    len: usize,
+    next: usize,
+    // Codes on the display per row, valid once drawn
+    cells: [[u8; MAX_COLUMNS]; 2],
+    drawn: bool,
+}
+
+impl<const N: usize> Sparkline<N> {
+    /// Creates a graph `width` cells wide and `height` rows high with its upper
+    /// left corner at a column and row of the display. The width is cut down to
+    /// `N` and 40, the height to 1 or 2.
+    pub fn new(column: u8, row: u8, width: u8, height: u8) -> Sparkline<N> {
+        Sparkline {
+            column,
+            row,
+            width: width.min(N.min(MAX_COLUMNS) as u8),
+            height: height.clamp(1, 2),
+            scale: Scale::Auto,
+            samples: [0; N],
+            len: 0,
+            next: 0,
+            cells: [[b' '; MAX_COLUMNS]; 2],
+            drawn: false,
+        }
+    }
+
+    /// Sets how samples are mapped to bar heights.
+    ///
+    /// Default is [Scale::Auto].
+    pub fn set_scale(&mut self, scale: Scale) -> &mut Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Adds a sample, dropping the oldest once `N` are stored. The display is
+    /// only updated by [draw](#method.draw).
+    pub fn push(&mut self, sample: i32) {
+        if N == 0 {
+            return;
+        }
+
+        self.samples[self.next] = sample;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+
+    /// Drops all samples.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.next = 0;
+    }
+
+    /// Makes the next draw rewrite every cell, for when the display has been
+    /// cleared or written over.
+    pub fn invalidate(&mut self) {
+        self.drawn = false;
+    }
+
+    /// Stores the eight bar glyphs in CGRAM slots 0 to 7.
+    pub fn load<B: DataBus, D: DelayNs>(
+        &self,
+        lcd: &mut LCD1602<B>,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        for level in 1..=CELL_LEVELS {
+            let mut glyph = [0; 8];
+            for line in glyph[(CELL_LEVELS - level) as usize..].iter_mut() {
+                *line = 0b11111;
+            }
+
+            lcd.create_char(level - 1, &glyph, delay)?;
+        }
+
+        Ok(())
+    }
+
+    /// Shows the stored samples, rewriting only the cells that changed.
+    /// Columns without a sample yet are left blank.
+    ///
+    /// Returns an error if the graph does not fit on the display.
+    pub fn draw<B: DataBus, D: DelayNs>(
+        &mut self,
+        lcd: &mut LCD1602<B>,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        let cells = self.render();
+        let width = self.width as usize;
+
+        for (line, cells) in cells.iter().enumerate().take(self.height as usize) {
+            update_row(
+                lcd,
+                self.column,
+                self.row.saturating_add(line as u8),
+                &cells[..width],
+                &mut self.cells[line][..width],
+                !self.drawn,
+                delay,
+            )?;
+        }
+
+        self.drawn = true;
+
+        Ok(())
+    }
+
+    // The shown samples, oldest first
+    fn shown(&self) -> impl Iterator<Item = i32> + '_ {
+        let count = self.len.min(self.width as usize);
+        (N + self.next - count..N + self.next).map(move |index| self.samples[index % N])
+    }
+
+    fn render(&self) -> [[u8; MAX_COLUMNS]; 2] {
+        let mut cells = [[b' '; MAX_COLUMNS]; 2];
+        let levels = (CELL_LEVELS * self.height) as i64;
+
+        let (min, max) = match self.scale {
+            Scale::Fixed { min, max } => (min, max),
+            Scale::Auto => self
+                .shown()
+                .fold((i32::MAX, i32::MIN), |(min, max), sample| {
+                    (min.min(sample), max.max(sample))
+                }),
+        };
+
+        let blank = self.width as usize - self.len.min(self.width as usize);
+        for (column, sample) in (blank..).zip(self.shown()) {
+            // Every sample gets at least the lowest bar, so it differs from no sample
+            let level = if max > min {
+                let sample = sample.clamp(min, max) as i64;
+                1 + (sample - min as i64) * (levels - 1) / (max as i64 - min as i64)
+            } else {
+                (levels + 1) / 2
+            } as u8;
+
+            let bottom = self.height as usize - 1;
+            cells[bottom][column] = level.min(CELL_LEVELS) - 1;
+            if level > CELL_LEVELS {
+                cells[0][column] = level - CELL_LEVELS - 1;
+            }
+        }
+
+        cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_bus::{NoDelay, TestBus};
+    use crate::Geometry;
+
+    fn row(lcd: &LCD1602<TestBus>, row: u8, width: usize) -> [u8; 40] {
+        let mut cells = lcd.bus().row(Geometry::LCD16X2, row);
+        cells[width..].fill(0);
+        cells
+    }
+
+    fn expected(cells: &[u8]) -> [u8; 40] {
+        let mut row = [0; 40];
+        row[..cells.len()].copy_from_slice(cells);
+        row
+    }
+
+    #[test]
+    fn scales_onto_bar_heights() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        let mut graph: Sparkline<8> = Sparkline::new(0, 0, 8, 1);
+        for sample in 0..8 {
+            graph.push(sample * 10 - 30);
+        }
+
+        graph.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(row(&lcd, 0, 8), expected(&[0, 1, 2, 3, 4, 5, 6, 7]));
+
+        graph.set_scale(Scale::Fixed { min: 0, max: 7 });
+        graph.clear();
+        for sample in [-5, 0, 3, 7, 100] {
+            graph.push(sample);
+        }
+
+        graph.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(row(&lcd, 0, 8), expected(b"   \x00\x00\x03\x07\x07"));
+    }
+
+    #[test]
+    fn two_rows() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        let mut graph: Sparkline<4> = Sparkline::new(0, 0, 3, 2);
+        for sample in [0, 8, 15] {
+            graph.push(sample);
+        }
+
+        graph.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(row(&lcd, 0, 3), expected(&[b' ', 0, 7]));
+        assert_eq!(row(&lcd, 1, 3), expected(&[0, 7, 7]));
+    }
+
+    #[test]
+    fn flat_and_empty() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        let mut graph: Sparkline<8> = Sparkline::new(0, 0, 5, 1);
+
+        graph.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(row(&lcd, 0, 5), expected(b"     "));
+
+        // Equal samples sit at half height
+        for _ in 0..3 {
+            graph.push(42);
+        }
+
+        graph.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(row(&lcd, 0, 5), expected(&[b' ', b' ', 3, 3, 3]));
+
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        let mut graph: Sparkline<8> = Sparkline::new(0, 0, 2, 2);
+        graph.push(-7);
+        graph.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(row(&lcd, 0, 2), expected(b"  "));
+        assert_eq!(row(&lcd, 1, 2), expected(&[b' ', 7]));
+    }
+
+    #[test]
+    fn scrolls_once_full() {
+        let mut lcd = LCD1602::test(Geometry::LCD16X2);
+        let mut graph: Sparkline<4> = Sparkline::new(0, 0, 4, 1);
+        graph.set_scale(Scale::Fixed { min: 0, max: 7 });
+        for sample in 0..4 {
+            graph.push(sample);
+        }
+
+        graph.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(row(&lcd, 0, 4), expected(&[0, 1, 2, 3]));
+
+        graph.push(7);
+        graph.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(row(&lcd, 0, 4), expected(&[1, 2, 3, 7]));
+
+        graph.push(5);
+        graph.push(6);
+        graph.draw(&mut lcd, &mut NoDelay).unwrap();
+        assert_eq!(row(&lcd, 0, 4), expected(&[3, 7, 5, 6]));
+    }
+}
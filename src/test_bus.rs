@@ -0,0 +1,105 @@
+// A display simulated well enough for unit tests to check what ends up in its
+// memory, driven through the same DataBus path as real hardware
+
+extern crate std;
+
+use std::vec::Vec;
+
+use embedded_hal::delay::DelayNs;
+
+use crate::command;
+use crate::data_bus::{Controller, DataBus};
+use crate::error::Result;
+use crate::geometry::MAX_COLUMNS;
+use crate::{Geometry, LCD1602};
+
+pub(crate) struct NoDelay;
+
+impl DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+pub(crate) struct TestBus {
+    // DDRAM of both controllers, indexed by address with bit 7 for the second
+    pub(crate) ddram: [u8; 256],
+    pub(crate) cgram: [u8; 64],
+    // Every byte written, with whether it was data
+    pub(crate) log: Vec<(bool, u8)>,
+    address: u8,
+    cgram_address: Option<u8>,
+    selected: Controller,
+}
+
+impl TestBus {
+    // Text of the cells of a row, as the geometry lays them out
+    pub(crate) fn row(&self, geometry: Geometry, row: u8) -> [u8; MAX_COLUMNS] {
+        let mut cells = [0u8; MAX_COLUMNS];
+        for (column, cell) in cells[..geometry.columns() as usize].iter_mut().enumerate() {
+            *cell = self.ddram[geometry.address(column as u8, row).unwrap() as usize];
+        }
+        cells
+    }
+}
+
+impl DataBus for TestBus {
+    type Error = ();
+
+    fn write<D: DelayNs>(&mut self, byte: u8, data: bool, _delay: &mut D) -> Result<(), ()> {
+        self.log.push((data, byte));
+
+        let second = match self.selected {
+            Controller::Second => 0x80,
+            _ => 0x00,
+        };
+
+        if data {
+            match self.cgram_address {
+                Some(address) => {
+                    self.cgram[address as usize & 0x3F] = byte;
+                    self.cgram_address = Some(address.wrapping_add(1));
+                }
+                None => {
+                    self.ddram[self.address as usize] = byte;
+                    self.address = command::next_address(self.address, true);
+                }
+            }
+        } else if byte & command::SET_DDRAM_ADDRESS != 0 {
+            self.address = second | (byte & command::MAX_DDRAM_ADDRESS);
+            self.cgram_address = None;
+        } else if byte & command::SET_CGRAM_ADDRESS != 0 {
+            self.cgram_address = Some(byte & 0x3F);
+        } else if byte == command::CLEAR_DISPLAY {
+            self.ddram = [b' '; 256];
+            self.address = second;
+            self.cgram_address = None;
+        }
+
+        Ok(())
+    }
+
+    fn select_controller(&mut self, controller: Controller) -> Result<(), ()> {
+        self.selected = controller;
+
+        Ok(())
+    }
+}
+
+impl LCD1602<TestBus> {
+    // A cleared display with the given geometry, skipping the wake up sequence
+    pub(crate) fn test(geometry: Geometry) -> LCD1602<TestBus> {
+        let bus = TestBus {
+            ddram: [b' '; 256],
+            cgram: [0; 64],
+            log: Vec::new(),
+            address: 0,
+            cgram_address: None,
+            selected: Controller::First,
+        };
+
+        LCD1602::from_parts(bus, geometry)
+    }
+
+    pub(crate) fn bus(&self) -> &TestBus {
+        &self.bus
+    }
+}
@@ -0,0 +1,297 @@
+use embedded_hal::delay::DelayNs;
+
+use crate::data_bus::DataBus;
+use crate::error::{Error, Result};
+use crate::geometry::MAX_COLUMNS;
+use crate::LCD1602;
+
+// Tab stops are every four columns
+const TAB_WIDTH: u8 = 4;
+
+/// How [write_str](crate::LCD1602::write_str) and [write_char](crate::LCD1602::write_char)
+/// treat text.
+///
+/// In every mode other than `Raw` the cursor is tracked in rows and columns of the
+/// display [geometry](crate::Geometry):
+///
+///  - `\n` moves to the start of the next row
+///  - `\r` moves to the start of the current row
+///  - `\t` writes spaces up to the next multiple of four columns
+///  - backspace (`\x08`) moves one column to the left without erasing
+///
+/// and text that reaches the end of a row continues at the start of the next one.
+/// The modes differ in what happens past the end of a row or of the last row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextMode {
+    /// Bytes are written as they are and follow the DDRAM address counter, so text
+    /// past the end of a row goes to memory that is not shown.
+    Raw,
+    /// Text past the last row continues on the first row.
+    Wrap,
+    /// Text past the end of a row is dropped until the next `\n`, and text past
+    /// the last row is dropped until the cursor is moved.
+    Truncate,
+    /// Text past the last row scrolls the display up by a row. The rows are
+    /// redrawn from a copy of everything written to the display, so this works
+    /// on buses that can't read as well.
+    Scroll,
+}
+
+// Copy of the characters written to DDRAM, for redrawing rows without reading
+// them back. Each controller has two lines of 40 characters.
+#[derive(Clone, Debug)]
+pub(crate) struct DdramShadow {
+    cells: [[u8; 2 * MAX_COLUMNS]; 2],
+}
+
+impl DdramShadow {
+    pub(crate) fn written(&mut self, address: u8, code: u8) {
+        if let Some((controller, index)) = Self::index(address) {
+            self.cells[controller][index] = code;
+        }
+    }
+
+    pub(crate) fn cleared(&mut self) {
+        *self = DdramShadow::default();
+    }
+
+    fn get(&self, address: u8) -> u8 {
+        Self::index(address).map_or(b' ', |(controller, index)| self.cells[controller][index])
+    }
+
+    // Controller and position in its lines of an address, if it is in DDRAM
+    fn index(address: u8) -> Option<(usize, usize)> {
+        let line = (address >> 6) as usize & 1;
+        let column = (address & 0x3F) as usize;
+
+        (column < MAX_COLUMNS).then_some(((address >> 7) as usize, line * MAX_COLUMNS + column))
+    }
+}
+
+impl Default for DdramShadow {
+    fn default() -> Self {
+        DdramShadow {
+            cells: [[b' '; 2 * MAX_COLUMNS]; 2],
+        }
+    }
+}
+
+impl Default for TextMode {
+    /// `Raw`, the way text was always written.
+    fn default() -> Self {
+        TextMode::Raw
+    }
+}
+
+impl<B> LCD1602<B>
+where
+    B: DataBus,
+{
+    /// Set how control characters and the end of a row are handled by
+    /// [write_str](#method.write_str) and [write_char](#method.write_char).
+    /// [write_byte](#method.write_byte) and [write_bytes](#method.write_bytes)
+    /// always write the bytes as they are, since custom characters use codes
+    /// 0 to 15.
+    ///
+    /// ```rust,ignore
+    /// lcd.set_text_mode(TextMode::Scroll);
+    /// lcd.write_str("boot ok\nwifi up\n", &mut delay)?;
+    /// ```
+    pub fn set_text_mode(&mut self, mode: TextMode) {
+        self.text_mode = mode;
+    }
+
+    /// Returns how text is written
+    pub fn text_mode(&self) -> TextMode {
+        self.text_mode
+    }
+
+    pub(crate) fn write_text<D: DelayNs>(
+        &mut self,
+        c: char,
+        mode: TextMode,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        if mode == TextMode::Raw {
+            let code = self.encode(c, delay)?;
+            self.write_byte(code, delay)?;
+
+            return match self.mark(c) {
+                Some(mark) => self.write_byte(mark, delay),
+                None => Ok(()),
+            };
+        }
+
+        match c {
+            '\n' => self.new_line(mode, delay),
+            '\r' => {
+                let row = self.text_row();
+                self.set_cursor(0, row, delay)
+            }
+            '\t' => loop {
+                self.put_char(b' ', mode, delay)?;
+
+                match (self.wrap_pending, self.geometry.position(self.address)) {
+                    (None, Some((column, _))) if column % TAB_WIDTH != 0 => {}
+                    _ => return Ok(()),
+                }
+            },
+            '\x08' => self.backspace(delay),
+            c => {
+                let code = self.encode(c, delay)?;
+                self.put_char(code, mode, delay)?;
+
+                match self.mark(c) {
+                    Some(mark) => self.put_char(mark, mode, delay),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+
+    // Row the text cursor is on, counting a pending wrap as still on its row
+    fn text_row(&self) -> u8 {
+        match self.wrap_pending {
+            Some(row) => row,
+            None => self
+                .geometry
+                .position(self.address)
+                .map_or(0, |(_, row)| row),
+        }
+    }
+
+    fn put_char<D: DelayNs>(
+        &mut self,
+        data: u8,
+        mode: TextMode,
+        delay: &mut D,
+    ) -> Result<(), B::Error> {
+        if self.wrap_pending.is_some() {
+            if mode == TextMode::Truncate {
+                return Ok(());
+            }
+
+            self.new_line(mode, delay)?;
+        }
+
+        let Some((column, row)) = self.geometry.position(self.address) else {
+            // Off the display, there is no row to wrap at
+            return self.write_byte(data, delay);
+        };
+
+        self.write_byte(data, delay)?;
+
+        if column + 1 == self.geometry.columns() {
+            // Only move on once there is something to put on the next row,
+            // so filling the last row does not scroll or wrap straight away
+            self.wrap_pending = Some(row);
+        } else if let Some(next) = self.geometry.address(column + 1, row) {
+            if next != self.address {
+                self.set_cursor_pos(next, delay)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn new_line<D: DelayNs>(&mut self, mode: TextMode, delay: &mut D) -> Result<(), B::Error> {
+        let row = self.text_row();
+        let last = self.geometry.rows().saturating_sub(1);
+
+        if row < last {
+            return self.set_cursor(0, row + 1, delay);
+        }
+
+        match mode {
+            TextMode::Raw | TextMode::Wrap => self.set_cursor(0, 0, delay),
+            TextMode::Truncate => {
+                self.wrap_pending = Some(last);
+                Ok(())
+            }
+            TextMode::Scroll => {
+                self.scroll_up(delay)?;
+                self.set_cursor(0, last, delay)
+            }
+        }
+    }
+
+    fn backspace<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), B::Error> {
+        if let Some(row) = self.wrap_pending {
+            let column = self.geometry.columns().saturating_sub(1);
+            return self.set_cursor(column, row, delay);
+        }
+
+        match self.geometry.position(self.address) {
+            Some((column, row)) if column > 0 => self.set_cursor(column - 1, row, delay),
+            _ => Ok(()),
+        }
+    }
+
+    // Move every row up by one and blank the last row, following the geometry
+    // where a row is not contiguous in DDRAM
+    fn scroll_up<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), B::Error> {
+        let columns = (self.geometry.columns() as usize).min(MAX_COLUMNS);
+        let rows = self.geometry.rows().min(4);
+        let mut line = [b' '; MAX_COLUMNS];
+
+        for row in 1..rows {
+            for (column, cell) in line[..columns].iter_mut().enumerate() {
+                let address = self
+                    .geometry
+                    .address(column as u8, row)
+                    .ok_or(Error::OutOfBounds)?;
+                *cell = self.ddram.get(address);
+            }
+
+            self.write_row(0, row - 1, &line[..columns], delay)?;
+        }
+
+        let blank = [b' '; MAX_COLUMNS];
+        self.write_row(0, rows.saturating_sub(1), &blank[..columns], delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_bus::NoDelay;
+    use crate::{Geometry, TextMode, LCD1602};
+
+    #[test]
+    fn scroll_moves_rows_up() {
+        let geometry = Geometry::LCD20X4;
+        let mut lcd = LCD1602::test(geometry);
+        lcd.set_text_mode(TextMode::Scroll);
+        lcd.write_str("one\ntwo\nthree\nfour\nfive", &mut NoDelay)
+            .unwrap();
+
+        let bus = lcd.bus();
+        assert_eq!(&bus.row(geometry, 0)[..6], b"two   ");
+        assert_eq!(&bus.row(geometry, 1)[..6], b"three ");
+        assert_eq!(&bus.row(geometry, 2)[..6], b"four  ");
+        assert_eq!(&bus.row(geometry, 3)[..6], b"five  ");
+    }
+
+    #[test]
+    fn scroll_blanks_split_row() {
+        let geometry = Geometry::LCD16X1;
+        let mut lcd = LCD1602::test(geometry);
+        lcd.set_text_mode(TextMode::Scroll);
+        lcd.write_str("0123456789abcdef\nxy", &mut NoDelay).unwrap();
+
+        let bus = lcd.bus();
+        assert_eq!(&bus.row(geometry, 0)[..16], b"xy              ");
+        // Memory between the two halves is not part of the row
+        assert_eq!(&bus.ddram[0x08..0x10], b"        ");
+    }
+
+    #[test]
+    fn wrap_follows_split_row() {
+        let geometry = Geometry::LCD16X1;
+        let mut lcd = LCD1602::test(geometry);
+        lcd.set_text_mode(TextMode::Wrap);
+        lcd.write_str("0123456789", &mut NoDelay).unwrap();
+
+        let bus = lcd.bus();
+        assert_eq!(&bus.row(geometry, 0)[..10], b"0123456789");
+    }
+}
@@ -0,0 +1,335 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+use crate::data_bus::{Backlight, DataBus, I2CBus, PinMap};
+use crate::display_control::{Cursor, CursorBlink, Display, DisplayMode};
+use crate::entry_mode::{CursorMode, ShiftMode};
+use crate::error::Result;
+use crate::{
+    Align, BigFont, CharacterRom, Direction, Font, FrameBuffer, Geometry, GlyphSet, TextMode,
+    Writer, LCD1602,
+};
+
+/**
+A [LCD1602] that owns its delay provider, so none of its methods take a delay.
+Create one with [LCD1602::with_delay], or with [new_i2c](#method.new_i2c) for the
+common PCF8574 backpack. The methods behave exactly like the ones on [LCD1602].
+
+```rust,ignore
+let mut lcd = LCD1602WithDelay::new_i2c(i2c, 0x27, delay)?;
+lcd.write_str("Hello, world!")?;
+let (bus, delay) = lcd.release();
+```
+*/
+pub struct LCD1602WithDelay<B: DataBus, D: DelayNs> {
+    lcd: LCD1602<B>,
+    delay: D,
+}
+
+impl<I2C: I2c, D: DelayNs> LCD1602WithDelay<I2CBus<I2C>, D> {
+    /// Create a new instance of the `LCD1602WithDelay` for a PCF8574 backpack,
+    /// see [LCD1602::new_i2c].
+    pub fn new_i2c(
+        i2c: I2C,
+        address: u8,
+        delay: D,
+    ) -> Result<LCD1602WithDelay<I2CBus<I2C>, D>, I2C::Error> {
+        Self::new_i2c_with_pin_map(i2c, address, PinMap::default(), delay)
+    }
+
+    /// Create a new instance of the `LCD1602WithDelay` for a PCF8574 backpack
+    /// wired as described by `pin_map`, see [LCD1602::new_i2c_with_pin_map].
+    pub fn new_i2c_with_pin_map(
+        i2c: I2C,
+        address: u8,
+        pin_map: PinMap,
+        mut delay: D,
+    ) -> Result<LCD1602WithDelay<I2CBus<I2C>, D>, I2C::Error> {
+        let lcd = LCD1602::new_i2c_with_pin_map(i2c, address, pin_map, &mut delay)?;
+
+        Ok(lcd.with_delay(delay))
+    }
+}
+
+impl<B: DataBus, D: DelayNs> LCD1602WithDelay<B, D> {
+    pub(crate) fn new(lcd: LCD1602<B>, delay: D) -> LCD1602WithDelay<B, D> {
+        LCD1602WithDelay { lcd, delay }
+    }
+
+    /// Gives back the bus and the delay provider.
+    pub fn release(self) -> (B, D) {
+        (self.lcd.release(), self.delay)
+    }
+
+    /// Borrows the wrapped [LCD1602] together with the delay provider, for
+    /// calling anything that still takes a delay.
+    pub fn parts(&mut self) -> (&mut LCD1602<B>, &mut D) {
+        (&mut self.lcd, &mut self.delay)
+    }
+
+    /// See [LCD1602::reset].
+    pub fn reset(&mut self) -> Result<(), B::Error> {
+        self.lcd.reset(&mut self.delay)
+    }
+
+    /// See [LCD1602::set_display_mode].
+    pub fn set_display_mode(&mut self, display_mode: DisplayMode) -> Result<(), B::Error> {
+        self.lcd.set_display_mode(display_mode, &mut self.delay)
+    }
+
+    /// See [LCD1602::clear].
+    pub fn clear(&mut self) -> Result<(), B::Error> {
+        self.lcd.clear(&mut self.delay)
+    }
+
+    /// See [LCD1602::set_autoscroll].
+    pub fn set_autoscroll(&mut self, enabled: ShiftMode) -> Result<(), B::Error> {
+        self.lcd.set_autoscroll(enabled, &mut self.delay)
+    }
+
+    /// See [LCD1602::set_cursor_visibility].
+    pub fn set_cursor_visibility(&mut self, visibility: Cursor) -> Result<(), B::Error> {
+        self.lcd.set_cursor_visibility(visibility, &mut self.delay)
+    }
+
+    /// See [LCD1602::set_display].
+    pub fn set_display(&mut self, display: Display) -> Result<(), B::Error> {
+        self.lcd.set_display(display, &mut self.delay)
+    }
+
+    /// See [LCD1602::set_cursor_blink].
+    pub fn set_cursor_blink(&mut self, blink: CursorBlink) -> Result<(), B::Error> {
+        self.lcd.set_cursor_blink(blink, &mut self.delay)
+    }
+
+    /// See [LCD1602::set_cursor_mode].
+    pub fn set_cursor_mode(&mut self, mode: CursorMode) -> Result<(), B::Error> {
+        self.lcd.set_cursor_mode(mode, &mut self.delay)
+    }
+
+    /// See [LCD1602::set_geometry].
+    pub fn set_geometry(&mut self, geometry: Geometry) {
+        self.lcd.set_geometry(geometry)
+    }
+
+    /// See [LCD1602::geometry].
+    pub fn geometry(&self) -> Geometry {
+        self.lcd.geometry()
+    }
+
+    /// See [LCD1602::set_font].
+    pub fn set_font(&mut self, font: Font) -> Result<(), B::Error> {
+        self.lcd.set_font(font, &mut self.delay)
+    }
+
+    /// See [LCD1602::set_cursor_pos].
+    pub fn set_cursor_pos(&mut self, position: u8) -> Result<(), B::Error> {
+        self.lcd.set_cursor_pos(position, &mut self.delay)
+    }
+
+    /// See [LCD1602::set_cursor].
+    pub fn set_cursor(&mut self, column: u8, row: u8) -> Result<(), B::Error> {
+        self.lcd.set_cursor(column, row, &mut self.delay)
+    }
+
+    /// See [LCD1602::shift_cursor].
+    pub fn shift_cursor(&mut self, dir: Direction) -> Result<(), B::Error> {
+        self.lcd.shift_cursor(dir, &mut self.delay)
+    }
+
+    /// See [LCD1602::shift_display].
+    pub fn shift_display(&mut self, dir: Direction) -> Result<(), B::Error> {
+        self.lcd.shift_display(dir, &mut self.delay)
+    }
+
+    /// See [LCD1602::write_char].
+    pub fn write_char(&mut self, data: char) -> Result<(), B::Error> {
+        self.lcd.write_char(data, &mut self.delay)
+    }
+
+    /// See [LCD1602::write_str].
+    pub fn write_str(&mut self, string: &str) -> Result<(), B::Error> {
+        self.lcd.write_str(string, &mut self.delay)
+    }
+
+    /// See [LCD1602::write_bytes].
+    pub fn write_bytes(&mut self, string: &[u8]) -> Result<(), B::Error> {
+        self.lcd.write_bytes(string, &mut self.delay)
+    }
+
+    /// See [LCD1602::write_byte].
+    pub fn write_byte(&mut self, data: u8) -> Result<(), B::Error> {
+        self.lcd.write_byte(data, &mut self.delay)
+    }
+
+    /// See [LCD1602::writer].
+    pub fn writer(&mut self) -> Writer<'_, B, D> {
+        self.lcd.writer(&mut self.delay)
+    }
+
+    /// See [LCD1602::flush].
+    pub fn flush<const COLS: usize, const ROWS: usize>(
+        &mut self,
+        frame: &mut FrameBuffer<COLS, ROWS>,
+    ) -> Result<(), B::Error> {
+        self.lcd.flush(frame, &mut self.delay)
+    }
+
+    /// See [LCD1602::create_char].
+    pub fn create_char(&mut self, slot: u8, glyph: &[u8; 8]) -> Result<(), B::Error> {
+        self.lcd.create_char(slot, glyph, &mut self.delay)
+    }
+
+    /// See [LCD1602::create_char_5x10].
+    pub fn create_char_5x10(&mut self, slot: u8, glyph: &[u8; 10]) -> Result<(), B::Error> {
+        self.lcd.create_char_5x10(slot, glyph, &mut self.delay)
+    }
+
+    /// See [LCD1602::release_char].
+    pub fn release_char(&mut self, slot: u8) -> Result<(), B::Error> {
+        self.lcd.release_char(slot)
+    }
+
+    /// See [LCD1602::read_cgram].
+    pub fn read_cgram(&mut self, slot: u8) -> Result<[u8; 8], B::Error> {
+        self.lcd.read_cgram(slot, &mut self.delay)
+    }
+
+    /// See [LCD1602::read_char_at].
+    pub fn read_char_at(&mut self, position: u8) -> Result<u8, B::Error> {
+        self.lcd.read_char_at(position, &mut self.delay)
+    }
+
+    /// See [LCD1602::cursor_address].
+    pub fn cursor_address(&mut self) -> Result<u8, B::Error> {
+        self.lcd.cursor_address(&mut self.delay)
+    }
+
+    /// See [LCD1602::set_text_mode].
+    pub fn set_text_mode(&mut self, mode: TextMode) {
+        self.lcd.set_text_mode(mode)
+    }
+
+    /// See [LCD1602::text_mode].
+    pub fn text_mode(&self) -> TextMode {
+        self.lcd.text_mode()
+    }
+
+    /// See [LCD1602::set_character_rom].
+    pub fn set_character_rom(&mut self, rom: &'static dyn CharacterRom) {
+        self.lcd.set_character_rom(rom)
+    }
+
+    /// See [LCD1602::set_fallback_char].
+    pub fn set_fallback_char(&mut self, code: u8) {
+        self.lcd.set_fallback_char(code)
+    }
+
+    /// See [LCD1602::set_glyph_sets].
+    pub fn set_glyph_sets(&mut self, glyph_sets: &'static [GlyphSet]) {
+        self.lcd.set_glyph_sets(glyph_sets)
+    }
+
+    /// See [LCD1602::load_big_font].
+    pub fn load_big_font(&mut self, font: &BigFont) -> Result<(), B::Error> {
+        self.lcd.load_big_font(font, &mut self.delay)
+    }
+
+    /// See [LCD1602::unload_big_font].
+    pub fn unload_big_font(&mut self, font: &BigFont) {
+        self.lcd.unload_big_font(font)
+    }
+
+    /// See [LCD1602::write_big_str].
+    pub fn write_big_str(
+        &mut self,
+        font: &BigFont,
+        text: &str,
+        column: u8,
+        row: u8,
+    ) -> Result<(), B::Error> {
+        self.lcd
+            .write_big_str(font, text, column, row, &mut self.delay)
+    }
+
+    /// See [LCD1602::set_decimal_separator].
+    pub fn set_decimal_separator(&mut self, separator: u8) {
+        self.lcd.set_decimal_separator(separator)
+    }
+
+    /// See [LCD1602::decimal_separator].
+    pub fn decimal_separator(&self) -> u8 {
+        self.lcd.decimal_separator()
+    }
+
+    /// See [LCD1602::write_u32].
+    pub fn write_u32(&mut self, value: u32, width: u8, align: Align) -> Result<(), B::Error> {
+        self.lcd.write_u32(value, width, align, &mut self.delay)
+    }
+
+    /// See [LCD1602::write_i32].
+    pub fn write_i32(&mut self, value: i32, width: u8, align: Align) -> Result<(), B::Error> {
+        self.lcd.write_i32(value, width, align, &mut self.delay)
+    }
+
+    /// See [LCD1602::write_fixed].
+    pub fn write_fixed(
+        &mut self,
+        value: i32,
+        decimals: u8,
+        width: u8,
+        align: Align,
+    ) -> Result<(), B::Error> {
+        self.lcd
+            .write_fixed(value, decimals, width, align, &mut self.delay)
+    }
+
+    /// See [LCD1602::write_hex].
+    pub fn write_hex(&mut self, value: u32, width: u8, align: Align) -> Result<(), B::Error> {
+        self.lcd.write_hex(value, width, align, &mut self.delay)
+    }
+
+    /// See [LCD1602::write_si].
+    pub fn write_si(
+        &mut self,
+        value: i32,
+        exponent: i8,
+        width: u8,
+        align: Align,
+    ) -> Result<(), B::Error> {
+        self.lcd
+            .write_si(value, exponent, width, align, &mut self.delay)
+    }
+
+    /// See [LCD1602::write_line].
+    pub fn write_line(&mut self, row: u8, text: &str, align: Align) -> Result<(), B::Error> {
+        self.lcd.write_line(row, text, align, &mut self.delay)
+    }
+
+    /// See [LCD1602::clear_row].
+    pub fn clear_row(&mut self, row: u8) -> Result<(), B::Error> {
+        self.lcd.clear_row(row, &mut self.delay)
+    }
+
+    /// See [LCD1602::clear_to_eol].
+    pub fn clear_to_eol(&mut self) -> Result<(), B::Error> {
+        self.lcd.clear_to_eol(&mut self.delay)
+    }
+}
+
+impl<B: Backlight, D: DelayNs> LCD1602WithDelay<B, D> {
+    /// See [LCD1602::set_backlight].
+    pub fn set_backlight(&mut self, on: bool) -> Result<(), B::Error> {
+        self.lcd.set_backlight(on)
+    }
+
+    /// See [LCD1602::backlight].
+    pub fn backlight(&self) -> bool {
+        self.lcd.backlight()
+    }
+
+    /// See [LCD1602::blink_backlight].
+    pub fn blink_backlight(&mut self, times: u8, period_ms: u32) -> Result<(), B::Error> {
+        self.lcd.blink_backlight(times, period_ms, &mut self.delay)
+    }
+}
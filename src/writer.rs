@@ -0,0 +1,66 @@
+use core::fmt;
+
+use embedded_hal::delay::DelayNs;
+
+use crate::data_bus::DataBus;
+use crate::error::Error;
+use crate::{TextMode, LCD1602};
+
+/// A handle to a [LCD1602] bound to a delay provider, returned by
+/// [LCD1602::writer]. It implements [core::fmt::Write], and `ufmt::uWrite`
+/// with the `ufmt` feature, so the display can be formatted into without an
+/// intermediate buffer.
+///
+/// `core::fmt::Write` can only report [fmt::Error]. The bus error behind a
+/// failed write is kept and can be taken with [Writer::take_error].
+pub struct Writer<'a, B: DataBus, D: DelayNs> {
+    lcd: &'a mut LCD1602<B>,
+    delay: &'a mut D,
+    error: Option<Error<B::Error>>,
+}
+
+impl<'a, B: DataBus, D: DelayNs> Writer<'a, B, D> {
+    pub(crate) fn new(lcd: &'a mut LCD1602<B>, delay: &'a mut D) -> Writer<'a, B, D> {
+        Writer {
+            lcd,
+            delay,
+            error: None,
+        }
+    }
+
+    /// Returns the error behind the last failed write, if any.
+    pub fn take_error(&mut self) -> Option<Error<B::Error>> {
+        self.error.take()
+    }
+
+    fn write_bytes(&mut self, string: &str) -> Result<(), Error<B::Error>> {
+        let mode = match self.lcd.text_mode() {
+            TextMode::Raw => TextMode::Wrap,
+            mode => mode,
+        };
+
+        for c in string.chars() {
+            self.lcd.write_text(c, mode, self.delay)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<B: DataBus, D: DelayNs> fmt::Write for Writer<'_, B, D> {
+    fn write_str(&mut self, string: &str) -> fmt::Result {
+        self.write_bytes(string).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl<B: DataBus, D: DelayNs> ufmt_write::uWrite for Writer<'_, B, D> {
+    type Error = Error<B::Error>;
+
+    fn write_str(&mut self, string: &str) -> Result<(), Error<B::Error>> {
+        self.write_bytes(string)
+    }
+}